@@ -0,0 +1,82 @@
+/// Converts an interleaved RGB buffer to grayscale exactly the way OpenCV's
+/// cvtColor(COLOR_RGB2GRAY) does for 8 bit images, which is what Gym's
+/// GrayScaleObservation wrapper uses. OpenCV approximates the 0.299/0.587/0.114
+/// weights in 14 bit fixed point and rounds half up, which occasionally
+/// differs by one from computing them exactly.
+pub fn grayscale_gym_compat(rgb: &[u8]) -> Vec<u8> {
+    const SHIFT: u32 = 14;
+    const R2Y: u32 = 4899;
+    const G2Y: u32 = 9617;
+    const B2Y: u32 = 1868;
+
+    rgb.chunks(3)
+       .map(|p| ((p[0] as u32 * R2Y + p[1] as u32 * G2Y + p[2] as u32 * B2Y + (1 << (SHIFT - 1))) >> SHIFT) as u8)
+       .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grayscale_gym_compat_matches_opencv() {
+        // Pixels (some from Breakout's palette) and the grays OpenCV's 8 bit
+        // RGB2GRAY conversion gives them. The last two land on the other side
+        // of a rounding boundary than the exact weights would.
+        let rgb = [
+            0, 0, 0,
+            255, 255, 255,
+            255, 0, 0,
+            0, 255, 0,
+            0, 0, 255,
+            200, 72, 72,
+            66, 72, 200,
+            142, 142, 142,
+            0, 27, 225,
+            0, 105, 69,
+        ];
+        let reference = [0, 255, 76, 150, 29, 110, 85, 142, 42, 69];
+
+        assert_eq!(grayscale_gym_compat(&rgb), reference);
+    }
+
+    #[test]
+    fn grayscale_gym_compat_matches_a_reference_frame() {
+        // A 16x6 Breakout-like frame: score, brick rows and the paddle, drawn
+        // from the game's palette. The luma was worked out separately from
+        // OpenCV's fixed point weights and rounding, pixel by pixel.
+        let palette = [
+            [0, 0, 0],
+            [200, 72, 72],
+            [198, 108, 58],
+            [180, 122, 48],
+            [162, 162, 42],
+            [72, 160, 72],
+            [66, 72, 200],
+            [142, 142, 142],
+        ];
+        let rows = [
+            "0000000000000000",
+            "7700000000000077",
+            "1111111111111111",
+            "2222222222222222",
+            "3333444455556666",
+            "0000000011110000",
+        ];
+        let rgb: Vec<u8> = rows.iter()
+            .flat_map(|row| row.bytes())
+            .flat_map(|c| palette[(c - b'0') as usize].iter().cloned())
+            .collect();
+
+        let reference: Vec<u8> = vec![
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            142, 142, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 142, 142,
+            110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110, 110,
+            129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129, 129,
+            131, 131, 131, 131, 148, 148, 148, 148, 124, 124, 124, 124, 85, 85, 85, 85,
+            0, 0, 0, 0, 0, 0, 0, 0, 110, 110, 110, 110, 0, 0, 0, 0,
+        ];
+
+        assert_eq!(grayscale_gym_compat(&rgb), reference);
+    }
+}
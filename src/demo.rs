@@ -0,0 +1,46 @@
+use std::fs::File;
+use std::io::{Read,Write};
+use std::path::Path;
+use ::rustc_serialize::json;
+use ::{Action,AleError};
+
+/// Records the (frame number, action) pairs of a demonstration, e.g. a human
+/// playing interactively, so it can be saved and replayed later with
+/// Game::replay_demo. Frame numbers are episode frame numbers
+/// (Game::episode_frame_number) read just before the action is taken, and only
+/// changes of action need recording since replays hold each action until the
+/// next recorded frame. Recordings should start right after a reset, since
+/// that's where replays start.
+#[derive(Clone, PartialEq, Eq, Debug, Default, RustcEncodable, RustcDecodable)]
+pub struct DemoRecorder {
+    pub steps: Vec<(i32, Action)>,
+}
+
+impl DemoRecorder {
+    pub fn new() -> DemoRecorder {
+        DemoRecorder::default()
+    }
+
+    /// Records that action was taken at the given episode frame number.
+    pub fn record(&mut self, frame: i32, action: Action) {
+        self.steps.push((frame, action));
+    }
+
+    /// Writes the recording to path as JSON.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AleError> {
+        let encoded = try!(json::encode(self).map_err(|e| AleError::Serialization(e.to_string())));
+
+        let mut file = try!(File::create(path));
+        try!(file.write_all(encoded.as_bytes()));
+
+        Ok(())
+    }
+
+    /// Reads a recording written by save.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<DemoRecorder, AleError> {
+        let mut encoded = String::new();
+        try!(try!(File::open(path)).read_to_string(&mut encoded));
+
+        json::decode(&encoded).map_err(|e| AleError::Serialization(e.to_string()))
+    }
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors produced by the Rust side of the wrapper. The C API itself
+/// doesn't report errors, so these cover misuse of the helpers layered on top.
+#[derive(Debug)]
+pub enum AleError {
+    /// Two frames that had to agree on their channel count didn't.
+    /// Holds (expected, found).
+    ChannelMismatch(usize, usize),
+    /// A buffer couldn't hold the data written to it.
+    /// Holds (needed, capacity) in bytes.
+    BufferTooSmall(usize, usize),
+    /// ALE's frame_skip and the crate's act repeat were both set above 1,
+    /// which would skip frames twice. Holds (frame_skip, act_repeat).
+    ConflictingFrameSkip(usize, usize),
+    /// The emulator didn't finish within the allotted time.
+    Timeout,
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// A list of actions and a list of per-action values had different
+    /// lengths. Holds (actions, values).
+    LengthMismatch(usize, usize),
+    /// An operation needed at least one action but got none.
+    NoActions,
+    /// Game::self_check found the ALE misbehaving, holds what went wrong.
+    SelfCheck(&'static str),
+    /// Encoding or decoding a saved file failed, holds the underlying error.
+    Serialization(String),
+    /// A replayed demonstration reached a recorded step after its frame.
+    /// Holds (recorded, actual) episode frame numbers.
+    DemoDrift(i32, i32),
+    /// The game doesn't know which ROM it's running, so it can't reload it.
+    NoRom,
+}
+
+impl fmt::Display for AleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AleError::ChannelMismatch(expected, found) => {
+                write!(f, "channel mismatch: expected {} channels, found {}", expected, found)
+            }
+            AleError::BufferTooSmall(needed, capacity) => {
+                write!(f, "buffer too small: needed {} bytes, capacity is {}", needed, capacity)
+            }
+            AleError::ConflictingFrameSkip(frame_skip, act_repeat) => {
+                write!(f, "frame_skip ({}) and act repeat ({}) can't both be above 1", frame_skip, act_repeat)
+            }
+            AleError::Timeout => write!(f, "timed out waiting for the emulator"),
+            AleError::Io(ref err) => write!(f, "io error: {}", err),
+            AleError::LengthMismatch(actions, values) => {
+                write!(f, "length mismatch: {} actions but {} values", actions, values)
+            }
+            AleError::NoActions => write!(f, "no actions to choose from"),
+            AleError::SelfCheck(problem) => write!(f, "self check failed: {}", problem),
+            AleError::Serialization(ref err) => write!(f, "serialization error: {}", err),
+            AleError::DemoDrift(recorded, actual) => {
+                write!(f, "demo drifted: step recorded at frame {} was reached at frame {}", recorded, actual)
+            }
+            AleError::NoRom => write!(f, "the game's ROM path is unknown"),
+        }
+    }
+}
+
+impl Error for AleError {
+    fn description(&self) -> &str {
+        match *self {
+            AleError::ChannelMismatch(..) => "frames have different channel counts",
+            AleError::BufferTooSmall(..) => "buffer too small",
+            AleError::ConflictingFrameSkip(..) => "frame_skip and act repeat are both active",
+            AleError::Timeout => "timed out waiting for the emulator",
+            AleError::Io(ref err) => err.description(),
+            AleError::LengthMismatch(..) => "actions and values have different lengths",
+            AleError::NoActions => "no actions to choose from",
+            AleError::SelfCheck(..) => "self check failed",
+            AleError::Serialization(..) => "serialization error",
+            AleError::DemoDrift(..) => "replayed demo drifted from the recording",
+            AleError::NoRom => "the game's ROM path is unknown",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            AleError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AleError {
+    fn from(err: io::Error) -> AleError {
+        AleError::Io(err)
+    }
+}
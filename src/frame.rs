@@ -0,0 +1,546 @@
+use ::AleError;
+
+/// An axis aligned rectangle in pixel coordinates, (x,y) being the top left corner.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Returns a crop that removes the score bars and borders for a handful of
+/// popular ROMs, or None (no crop) if the game isn't known. The name is
+/// matched case insensitively and ignoring punctuation and a .bin extension,
+/// so "SpaceInvaders", "space_invaders" and "space_invaders.bin" all match.
+pub fn crop_preset(game_name: &str) -> Option<Rect> {
+    let name = game_name.to_lowercase();
+    let name = if name.ends_with(".bin") { &name[..name.len() - 4] } else { &name[..] };
+    let name: String = name.chars().filter(|c| c.is_alphanumeric()).collect();
+
+    match &*name {
+        "breakout" => Some(Rect { x: 0, y: 32, width: 160, height: 178 }),
+        "pong" => Some(Rect { x: 0, y: 34, width: 160, height: 160 }),
+        "spaceinvaders" => Some(Rect { x: 0, y: 20, width: 160, height: 176 }),
+        _ => None,
+    }
+}
+
+/// The ways RGB can be converted to grayscale.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GrayMethod {
+    /// ITU-R 601 luminance with exact weights, rounded to nearest. This is
+    /// what Game::screen_grayscale uses.
+    Luminance,
+    /// OpenCV's fixed point approximation, matching Gym's preprocessing,
+    /// see compat::grayscale_gym_compat.
+    GymCompat,
+}
+
+/// Converts an interleaved RGB buffer to grayscale with the given method.
+pub fn grayscale_with(rgb: &[u8], method: GrayMethod) -> Vec<u8> {
+    match method {
+        GrayMethod::Luminance => grayscale(rgb),
+        GrayMethod::GymCompat => ::compat::grayscale_gym_compat(rgb),
+    }
+}
+
+/// Converts an interleaved RGB buffer to grayscale using the ITU-R 601
+/// luminance weights (0.299, 0.587, 0.114), rounding to the nearest value.
+pub fn grayscale(rgb: &[u8]) -> Vec<u8> {
+    rgb.chunks(3).map(luminance).collect()
+}
+
+/// Splits an interleaved RGB buffer (RGBRGB...) into three contiguous
+/// planes (RR...GG...BB...).
+pub fn planar_rgb(rgb: &[u8]) -> Vec<u8> {
+    let size = rgb.len() / 3;
+    let mut planar = vec![0; size * 3];
+
+    for (i, p) in rgb.chunks(3).enumerate() {
+        planar[i] = p[0];
+        planar[size + i] = p[1];
+        planar[2 * size + i] = p[2];
+    }
+
+    planar
+}
+
+/// The inverse of Game::screen_rgb_planar, turns three contiguous width x height
+/// channel planes back into an interleaved RGB buffer.
+pub fn interleave_rgb(planar: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let size = width * height;
+    assert_eq!(planar.len(), size * 3);
+
+    let mut rgb = Vec::<u8>::with_capacity(size * 3);
+    for i in 0..size {
+        rgb.push(planar[i]);
+        rgb.push(planar[size + i]);
+        rgb.push(planar[2 * size + i]);
+    }
+
+    rgb
+}
+
+fn luminance(p: &[u8]) -> u8 {
+    ((p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114 + 500) / 1000) as u8
+}
+
+/// A Frame is an owned copy of a screen (or a processed version of one).
+/// Pixels are stored row-major with their channels interleaved, so
+/// an RGB frame is laid out RGBRGB... and a grayscale frame has one byte per pixel.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub channels: usize,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    /// Wraps a pixel buffer. Panics if the buffer's length doesn't match
+    /// the given dimensions.
+    pub fn new(width: usize, height: usize, channels: usize, data: Vec<u8>) -> Frame {
+        assert_eq!(data.len(), width * height * channels);
+
+        Frame {
+            width: width,
+            height: height,
+            channels: channels,
+            data: data,
+        }
+    }
+
+    /// Creates an all-black frame of the given dimensions.
+    pub fn blank(width: usize, height: usize, channels: usize) -> Frame {
+        Frame::new(width, height, channels, vec![0; width * height * channels])
+    }
+
+    /// Whether the frame has 3 (interleaved RGB) channels.
+    pub fn is_rgb(&self) -> bool {
+        self.channels == 3
+    }
+
+    /// Whether the frame has a single channel.
+    pub fn is_grayscale(&self) -> bool {
+        self.channels == 1
+    }
+
+    /// Converts an RGB frame to grayscale with the same luminance weights as
+    /// the grayscale function. A grayscale frame is just copied. Panics on
+    /// any other channel count.
+    pub fn to_grayscale(&self) -> Frame {
+        match self.channels {
+            1 => self.clone(),
+            3 => Frame::new(self.width, self.height, 1, grayscale(&self.data)),
+            n => panic!("can't convert a {} channel frame to grayscale", n),
+        }
+    }
+
+    /// Converts a grayscale frame to RGB by copying its channel into all
+    /// three. An RGB frame is just copied. Panics on any other channel count.
+    pub fn to_rgb(&self) -> Frame {
+        match self.channels {
+            1 => {
+                let mut data = Vec::<u8>::with_capacity(self.data.len() * 3);
+                for &v in &self.data {
+                    data.extend_from_slice(&[v, v, v]);
+                }
+
+                Frame::new(self.width, self.height, 3, data)
+            }
+            3 => self.clone(),
+            n => panic!("can't convert a {} channel frame to RGB", n),
+        }
+    }
+
+    /// Returns the channels of the pixel at (x,y).
+    pub fn pixel(&self, x: usize, y: usize) -> &[u8] {
+        let start = (y * self.width + x) * self.channels;
+        &self.data[start..start + self.channels]
+    }
+
+    /// Copies src onto this frame with its top left corner at (dst_x,dst_y).
+    /// Anything falling outside this frame is clipped. Both frames must have
+    /// the same number of channels.
+    pub fn blit(&mut self, src: &Frame, dst_x: usize, dst_y: usize) -> Result<(), AleError> {
+        use std::cmp::min;
+
+        if src.channels != self.channels {
+            return Err(AleError::ChannelMismatch(self.channels, src.channels));
+        }
+
+        if dst_x >= self.width || dst_y >= self.height {
+            return Ok(());
+        }
+
+        let cols = min(src.width, self.width - dst_x);
+        let rows = min(src.height, self.height - dst_y);
+        let row_len = cols * self.channels;
+
+        for row in 0..rows {
+            let src_start = row * src.width * src.channels;
+            let dst_start = ((dst_y + row) * self.width + dst_x) * self.channels;
+
+            self.data[dst_start..dst_start + row_len]
+                .copy_from_slice(&src.data[src_start..src_start + row_len]);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the part of this frame inside rect. The rect is clipped to the
+    /// frame, so the result may be smaller than requested.
+    pub fn crop(&self, rect: Rect) -> Frame {
+        use std::cmp::min;
+
+        let x = min(rect.x, self.width);
+        let y = min(rect.y, self.height);
+        let width = min(rect.width, self.width - x);
+        let height = min(rect.height, self.height - y);
+
+        let mut data = Vec::<u8>::with_capacity(width * height * self.channels);
+        for row in y..y + height {
+            let start = (row * self.width + x) * self.channels;
+            data.extend_from_slice(&self.data[start..start + width * self.channels]);
+        }
+
+        Frame::new(width, height, self.channels, data)
+    }
+
+    /// Resizes the frame to width x height. Every output pixel is the average
+    /// of the source pixels it covers (at least one), which gives area
+    /// averaging when shrinking and nearest neighbour when growing.
+    pub fn resize(&self, width: usize, height: usize) -> Frame {
+        use std::cmp::max;
+
+        let mut data = Vec::<u8>::with_capacity(width * height * self.channels);
+        let mut sums = vec![0usize; self.channels];
+
+        for y in 0..height {
+            let y0 = y * self.height / height;
+            let y1 = max(y0 + 1, (y + 1) * self.height / height);
+
+            for x in 0..width {
+                let x0 = x * self.width / width;
+                let x1 = max(x0 + 1, (x + 1) * self.width / width);
+
+                for sum in sums.iter_mut() {
+                    *sum = 0;
+                }
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        for (sum, &v) in sums.iter_mut().zip(self.pixel(sx, sy)) {
+                            *sum += v as usize;
+                        }
+                    }
+                }
+
+                let count = (y1 - y0) * (x1 - x0);
+                data.extend(sums.iter().map(|&sum| (sum / count) as u8));
+            }
+        }
+
+        Frame::new(width, height, self.channels, data)
+    }
+
+    /// Reduces every value to its top bits bits by masking off the rest,
+    /// e.g. 1 bit leaves only 0 and 128. Useful for shrinking replay buffers
+    /// at the cost of precision. Panics unless bits is between 1 and 8.
+    pub fn quantize(&self, bits: u8) -> Frame {
+        assert!(bits >= 1 && bits <= 8, "can't quantize to {} bits", bits);
+
+        let mask = 0xFFu8 << (8 - bits);
+
+        Frame::new(self.width, self.height, self.channels,
+                   self.data.iter().map(|&v| v & mask).collect())
+    }
+
+    /// Stamps text onto the frame with a tiny built in 3x5 pixel font, the
+    /// top left of the first character at (x,y). Each character advances
+    /// 4 pixels and a newline moves down 6. The font covers digits, letters
+    /// (drawn uppercase) and a little punctuation, anything else is drawn as
+    /// '?'. Text running off the frame is clipped. On frames that aren't RGB
+    /// the color's luminance is written to every channel.
+    pub fn draw_text(&mut self, text: &str, x: usize, y: usize, color: [u8; 3]) {
+        use ::font::{glyph,GLYPH_WIDTH,GLYPH_HEIGHT};
+
+        let gray = luminance(&color);
+        let (mut cx, mut cy) = (x, y);
+
+        for c in text.chars() {
+            if c == '\n' {
+                cx = x;
+                cy += GLYPH_HEIGHT + 1;
+                continue;
+            }
+
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                        continue;
+                    }
+
+                    let (px, py) = (cx + col, cy + row);
+                    if px >= self.width || py >= self.height {
+                        continue;
+                    }
+
+                    let start = (py * self.width + px) * self.channels;
+                    let pixel = &mut self.data[start..start + self.channels];
+                    if pixel.len() == 3 {
+                        pixel.copy_from_slice(&color);
+                    } else {
+                        for v in pixel.iter_mut() {
+                            *v = gray;
+                        }
+                    }
+                }
+            }
+
+            cx += GLYPH_WIDTH + 1;
+        }
+    }
+
+    /// Renders the frame as ASCII art cols characters wide, one line per row
+    /// of characters (each ending in a newline). Rows are sampled at half the
+    /// horizontal rate since terminal characters are about twice as tall as
+    /// they are wide.
+    pub fn to_ascii(&self, cols: usize) -> String {
+        use std::cmp::{max,min};
+
+        const RAMP: &'static [u8] = b" .:-=+*#%@";
+
+        if cols == 0 || self.width == 0 || self.height == 0 {
+            return String::new();
+        }
+
+        let cols = min(cols, self.width);
+        let rows = max(1, self.height * cols / (2 * self.width));
+        let mut out = String::with_capacity((cols + 1) * rows);
+
+        for row in 0..rows {
+            let (y0, y1) = (row * self.height / rows, (row + 1) * self.height / rows);
+
+            for col in 0..cols {
+                let (x0, x1) = (col * self.width / cols, (col + 1) * self.width / cols);
+
+                let mut sum = 0;
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        sum += self.luma(x, y) as usize;
+                    }
+                }
+                let mean = sum / ((y1 - y0) * (x1 - x0));
+
+                out.push(RAMP[mean * (RAMP.len() - 1) / 255] as char);
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    // The brightness of a pixel, using the luminance weights for RGB frames
+    // and the first channel otherwise.
+    fn luma(&self, x: usize, y: usize) -> u8 {
+        let p = self.pixel(x, y);
+        if self.channels == 3 {
+            luminance(p)
+        } else {
+            p[0]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::AleError;
+
+    #[test]
+    fn crop_preset_knows_popular_games() {
+        for name in &["Breakout", "pong", "space_invaders.bin", "SpaceInvaders"] {
+            let rect = crop_preset(name).expect(name);
+
+            assert!(rect.width > 0 && rect.height > 0);
+            assert!(rect.x + rect.width <= 160 && rect.y + rect.height <= 210);
+        }
+    }
+
+    #[test]
+    fn crop_preset_returns_none_for_unknown_games() {
+        assert_eq!(crop_preset("montezuma_revenge"), None);
+        assert_eq!(crop_preset(""), None);
+    }
+
+    #[test]
+    fn blit_places_pixels_at_offset() {
+        let mut canvas = Frame::blank(4, 3, 1);
+        let src = Frame::new(2, 2, 1, vec![1, 2, 3, 4]);
+
+        canvas.blit(&src, 1, 1).unwrap();
+
+        assert_eq!(canvas.data, vec![0, 0, 0, 0,
+                                     0, 1, 2, 0,
+                                     0, 3, 4, 0]);
+    }
+
+    #[test]
+    fn blit_clips_at_borders() {
+        let mut canvas = Frame::blank(3, 3, 1);
+        let src = Frame::new(2, 2, 1, vec![1, 2, 3, 4]);
+
+        canvas.blit(&src, 2, 2).unwrap();
+        assert_eq!(canvas.data, vec![0, 0, 0,
+                                     0, 0, 0,
+                                     0, 0, 1]);
+
+        canvas.blit(&src, 3, 0).unwrap();
+        assert_eq!(canvas.data, vec![0, 0, 0,
+                                     0, 0, 0,
+                                     0, 0, 1]);
+    }
+
+    #[test]
+    fn blit_rejects_channel_mismatch() {
+        let mut canvas = Frame::blank(3, 3, 3);
+        let src = Frame::blank(1, 1, 1);
+
+        match canvas.blit(&src, 0, 0) {
+            Err(AleError::ChannelMismatch(3, 1)) => {}
+            other => panic!("expected a channel mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn to_ascii_has_one_line_per_row_of_ramp_characters() {
+        let frame = Frame::new(8, 4, 1, (0..32).map(|i| (i * 8) as u8).collect());
+        let art = frame.to_ascii(8);
+
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(art.ends_with('\n'));
+        for line in lines {
+            assert_eq!(line.len(), 8);
+            assert!(line.chars().all(|c| " .:-=+*#%@".contains(c)), "unexpected character in {:?}", line);
+        }
+    }
+
+    #[test]
+    fn to_ascii_maps_black_and_white_to_the_ramp_ends() {
+        assert_eq!(Frame::blank(4, 2, 3).to_ascii(4), "    \n");
+        assert_eq!(Frame::new(4, 2, 1, vec![255; 8]).to_ascii(4), "@@@@\n");
+    }
+
+    #[test]
+    fn planar_rgb_splits_channels_into_planes() {
+        let rgb = vec![1, 2, 3, 4, 5, 6];
+        assert_eq!(planar_rgb(&rgb), vec![1, 4, 2, 5, 3, 6]);
+    }
+
+    #[test]
+    fn interleave_rgb_undoes_planar_rgb() {
+        let rgb: Vec<u8> = (0..4 * 3 * 3).map(|i| (i * 7) as u8).collect();
+        let planar = planar_rgb(&rgb);
+
+        assert_eq!(interleave_rgb(&planar, 4, 3), rgb);
+        assert_eq!(planar_rgb(&interleave_rgb(&planar, 4, 3)), planar);
+    }
+
+    // Renders a single channel frame as rows of '#' (lit) and '.' (dark).
+    fn mask(frame: &Frame) -> Vec<String> {
+        frame.data.chunks(frame.width)
+                  .map(|row| row.iter().map(|&v| if v == 0 { '.' } else { '#' }).collect())
+                  .collect()
+    }
+
+    #[test]
+    fn draw_text_stamps_glyphs_side_by_side() {
+        let mut frame = Frame::blank(8, 5, 1);
+        frame.draw_text("10", 0, 0, [255, 255, 255]);
+
+        assert_eq!(mask(&frame), vec![
+            ".#..###.",
+            "##..#.#.",
+            ".#..#.#.",
+            ".#..#.#.",
+            "###.###.",
+        ]);
+    }
+
+    #[test]
+    fn draw_text_colors_rgb_and_grays_other_frames() {
+        let mut rgb = Frame::blank(4, 5, 3);
+        rgb.draw_text("1", 0, 0, [255, 0, 0]);
+        assert_eq!(&rgb.data[3..6], &[255, 0, 0]);
+        assert_eq!(&rgb.data[..3], &[0, 0, 0]);
+
+        let mut gray = Frame::blank(4, 5, 1);
+        gray.draw_text("1", 0, 0, [255, 0, 0]);
+        assert_eq!(gray.data[1], 76);
+    }
+
+    #[test]
+    fn draw_text_clips_and_wraps_lines() {
+        let mut frame = Frame::blank(3, 10, 1);
+        frame.draw_text("1\n1", 1, 2, [255, 255, 255]);
+
+        assert_eq!(mask(&frame), vec![
+            "...",
+            "...",
+            "..#",
+            ".##",
+            "..#",
+            "..#",
+            ".##",
+            "...",
+            "..#",
+            ".##",
+        ]);
+    }
+
+    #[test]
+    fn quantize_keeps_the_top_bits() {
+        let frame = Frame::new(5, 1, 1, vec![0, 1, 127, 128, 255]);
+
+        assert_eq!(frame.quantize(1).data, vec![0, 0, 0, 128, 128]);
+        assert_eq!(frame.quantize(3).data, vec![0, 0, 96, 128, 224]);
+        assert_eq!(frame.quantize(8), frame);
+    }
+
+    #[test]
+    #[should_panic]
+    fn quantize_rejects_zero_bits() {
+        Frame::blank(1, 1, 1).quantize(0);
+    }
+
+    #[test]
+    fn to_grayscale_weighs_the_channels() {
+        let rgb = Frame::new(3, 1, 3, vec![255, 0, 0, 0, 255, 0, 10, 20, 30]);
+        let gray = rgb.to_grayscale();
+
+        assert!(rgb.is_rgb() && !rgb.is_grayscale());
+        assert!(gray.is_grayscale() && !gray.is_rgb());
+        assert_eq!((gray.width, gray.height), (3, 1));
+        assert_eq!(gray.data, vec![76, 150, 18]);
+        assert_eq!(gray.to_grayscale(), gray);
+    }
+
+    #[test]
+    fn to_rgb_copies_the_gray_channel() {
+        let gray = Frame::new(2, 1, 1, vec![7, 200]);
+        let rgb = gray.to_rgb();
+
+        assert!(rgb.is_rgb());
+        assert_eq!(rgb.data, vec![7, 7, 7, 200, 200, 200]);
+        assert_eq!(rgb.to_rgb(), rgb);
+        assert_eq!(rgb.to_grayscale(), gray);
+    }
+
+    #[test]
+    #[should_panic]
+    fn to_grayscale_rejects_other_channel_counts() {
+        Frame::blank(1, 1, 2).to_grayscale();
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::{HashMap,VecDeque};
+use ::Frame;
+
+/// A small least-recently-used cache of frames keyed by state fingerprint.
+/// Capacities are expected to be small (tens to hundreds of entries), so
+/// recency is tracked with a plain queue.
+pub struct ObsCache {
+    capacity: usize,
+    frames: HashMap<u64, Frame>,
+    order: VecDeque<u64>,
+    hits: usize,
+    misses: usize,
+}
+
+impl ObsCache {
+    pub fn new(capacity: usize) -> ObsCache {
+        ObsCache {
+            capacity: capacity,
+            frames: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: u64) -> Option<&Frame> {
+        if self.frames.contains_key(&key) {
+            self.hits += 1;
+            self.touch(key);
+        } else {
+            self.misses += 1;
+        }
+
+        self.frames.get(&key)
+    }
+
+    pub fn insert(&mut self, key: u64, frame: Frame) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.frames.insert(key, frame).is_some() {
+            self.touch(key);
+            return;
+        }
+
+        self.order.push_back(key);
+        if self.order.len() > self.capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.frames.remove(&old);
+            }
+        }
+    }
+
+    /// The number of lookups that found a frame and that didn't, since the
+    /// cache was created.
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits, self.misses)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.order.clear();
+    }
+
+    fn touch(&mut self, key: u64) {
+        if let Some(pos) = self.order.iter().position(|&k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+}
@@ -0,0 +1,53 @@
+use std::fs::{self,File};
+use std::io::{self,Write};
+use std::path::{Path,PathBuf};
+
+/// How many checkpoint files are rotated through.
+pub const CHECKPOINT_FILES: usize = 3;
+
+/// Tracks when the next periodic checkpoint is due and where it goes.
+pub struct Checkpointer {
+    interval: i32,
+    dir: PathBuf,
+    next_frame: i32,
+    written: usize,
+}
+
+impl Checkpointer {
+    pub fn new(interval: i32, dir: &Path, frame: i32) -> Checkpointer {
+        Checkpointer {
+            interval: interval,
+            dir: dir.to_path_buf(),
+            next_frame: frame + interval,
+            written: 0,
+        }
+    }
+
+    /// If a checkpoint is due at this frame number, returns the path it
+    /// should be written to and schedules the next one.
+    pub fn due(&mut self, frame: i32) -> Option<PathBuf> {
+        if frame < self.next_frame {
+            return None;
+        }
+
+        let path = self.dir.join(format!("checkpoint-{}.state", self.written % CHECKPOINT_FILES));
+        self.next_frame = frame + self.interval;
+        self.written += 1;
+
+        Some(path)
+    }
+}
+
+/// Writes bytes to a temporary file next to path and renames it over path,
+/// so readers only ever see a complete file.
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("state.tmp");
+
+    {
+        let mut file = try!(File::create(&tmp));
+        try!(file.write_all(bytes));
+        try!(file.sync_all());
+    }
+
+    fs::rename(&tmp, path)
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+use ::{Action,AleError,DemoRecorder};
+use super::Game;
+
+impl Game {
+    /// Resets the game and replays a recording saved by DemoRecorder, returning
+    /// the total reward and whether the game ended. See replay_recording.
+    pub fn replay_demo<P: AsRef<Path>>(&mut self, path: P) -> Result<(i32, bool), AleError> {
+        let demo = try!(DemoRecorder::load(path));
+
+        self.replay_recording(&demo)
+    }
+
+    /// Resets the game and replays a recording, returning the total reward and
+    /// whether the game ended. Each action is taken at its recorded episode
+    /// frame number and held until the next one, with NOOPs before the first.
+    /// Playback stops early if the game ends. The reset never plays reset
+    /// NOOPs (see set_reset_noops), so recordings should start after a reset
+    /// with them turned off.
+    ///
+    /// Replays are only faithful if the ALE is configured the same way as when
+    /// recording, in particular with the same seed, sticky action probability,
+    /// frame_skip and act repeat. If the game has already passed a recorded
+    /// frame when its action is due, the replay has drifted and stops with
+    /// AleError::DemoDrift.
+    pub fn replay_recording(&mut self, demo: &DemoRecorder) -> Result<(i32, bool), AleError> {
+        self.reset_without_noops();
+
+        let mut reward = 0;
+        let mut held = Action(0);
+        for &(frame, action) in &demo.steps {
+            while self.episode_frame_number() < frame && !self.is_over() {
+                reward += self.act(held);
+            }
+            if self.is_over() {
+                break;
+            }
+
+            let actual = self.episode_frame_number();
+            if actual != frame {
+                return Err(AleError::DemoDrift(frame, actual));
+            }
+
+            reward += self.act(action);
+            held = action;
+        }
+
+        Ok((reward, self.is_over()))
+    }
+}
@@ -0,0 +1,55 @@
+use ::{Action,Frame};
+use super::Game;
+
+/// Aggregate returns over the episodes played by Game::evaluate.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EvalReport {
+    pub mean_return: f32,
+    /// The population standard deviation of the returns.
+    pub std_return: f32,
+    pub min: i32,
+    pub max: i32,
+    /// The return of every episode, in the order they were played.
+    pub returns: Vec<i32>,
+}
+
+impl Game {
+    /// Plays episodes full episodes, resetting before each one, choosing every
+    /// action by calling policy on the current observation (see Game::observation).
+    /// Rewards are counted after any reward transform.
+    ///
+    /// Episodes only end when the game does, so to truncate long episodes set
+    /// ALE's max_num_frames_per_episode before loading the ROM. With zero
+    /// episodes every statistic is zero.
+    pub fn evaluate<F: FnMut(&Frame) -> Action>(&mut self, episodes: usize, mut policy: F) -> EvalReport {
+        let mut returns = Vec::<i32>::with_capacity(episodes);
+
+        for _ in 0..episodes {
+            self.reset();
+
+            let mut total = 0;
+            while !self.is_over() {
+                let obs = self.observation();
+                total += self.act(policy(&obs));
+            }
+
+            returns.push(total);
+        }
+
+        if returns.is_empty() {
+            return EvalReport { mean_return: 0.0, std_return: 0.0, min: 0, max: 0, returns: returns };
+        }
+
+        let n = returns.len() as f32;
+        let mean = returns.iter().fold(0.0, |acc, &r| acc + r as f32) / n;
+        let var = returns.iter().fold(0.0, |acc, &r| acc + (r as f32 - mean) * (r as f32 - mean)) / n;
+
+        EvalReport {
+            mean_return: mean,
+            std_return: var.sqrt(),
+            min: *returns.iter().min().unwrap(),
+            max: *returns.iter().max().unwrap(),
+            returns: returns,
+        }
+    }
+}
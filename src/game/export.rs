@@ -0,0 +1,98 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use ::gif;
+use ::png;
+use ::{Action,AleError,Frame};
+use super::Game;
+
+impl Game {
+    /// Resets the game and plays a full episode with the given policy, saving
+    /// every RGB frame to an animated GIF at path, played back at fps frames
+    /// per second. The reset never plays reset NOOPs (see set_reset_noops),
+    /// so the GIF shows the episode from its very first frame.
+    ///
+    /// GIF delays are whole hundredths of a second, and most viewers play
+    /// delays below 2 as if they were 10, so fps is capped at 50. An fps of 0
+    /// is treated as 1.
+    ///
+    /// GIF frames are limited to 256 colors, so each frame gets its own palette
+    /// quantized from its pixels. Atari frames use few enough colors that this is
+    /// rarely lossy, but it isn't fast.
+    pub fn run_and_save_gif<F, P>(&mut self, mut policy: F, path: P, fps: u16) -> Result<(), AleError>
+        where F: FnMut(&Frame) -> Action,
+              P: AsRef<Path>
+    {
+        use ::gif::SetParameter;
+
+        let (width, height) = self.screen_dimensions();
+        let file = BufWriter::new(try!(File::create(path)));
+        let mut encoder = try!(gif::Encoder::new(file, width as u16, height as u16, &[]));
+        try!(encoder.set(gif::Repeat::Infinite));
+
+        let delay = gif_delay(fps);
+
+        self.reset_without_noops();
+        loop {
+            let frame = self.frame();
+
+            let mut gif_frame = gif::Frame::from_rgb(width as u16, height as u16, &frame.data);
+            gif_frame.delay = delay;
+            try!(encoder.write_frame(&gif_frame));
+
+            if self.is_over() {
+                break;
+            }
+
+            let action = policy(&frame);
+            self.act(action);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the RGB screen as a PNG data URI (data:image/png;base64,...),
+    /// so dashboards and notebooks can display it without touching the disk.
+    pub fn screen_data_uri(&self) -> String {
+        use ::png::HasParameters;
+        use ::rustc_serialize::base64::{ToBase64,STANDARD};
+
+        let (width, height) = self.screen_dimensions();
+        let mut bytes = Vec::<u8>::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+            encoder.set(png::ColorType::RGB).set(png::BitDepth::Eight);
+
+            let mut writer = encoder.write_header().expect("Could not write PNG header");
+            writer.write_image_data(&self.screen_rgb()).expect("Could not write PNG data");
+        }
+
+        format!("data:image/png;base64,{}", bytes.to_base64(STANDARD))
+    }
+}
+
+// The GIF frame delay, in hundredths of a second, for playback at fps.
+fn gif_delay(fps: u16) -> u16 {
+    use std::cmp::max;
+
+    max(100 / max(fps, 1), 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gif_delay;
+
+    #[test]
+    fn gif_delay_treats_0_fps_as_1() {
+        assert_eq!(gif_delay(0), 100);
+        assert_eq!(gif_delay(1), 100);
+    }
+
+    #[test]
+    fn gif_delay_never_drops_below_2() {
+        assert_eq!(gif_delay(50), 2);
+        assert_eq!(gif_delay(60), 2);
+        assert_eq!(gif_delay(u16::max_value()), 2);
+        assert_eq!(gif_delay(30), 3);
+    }
+}
@@ -0,0 +1,51 @@
+use ::Action;
+use super::{Game,hash_bytes};
+
+/// A recorded trajectory for regression testing, see Game::record_golden.
+/// It can be saved with rustc_serialize (e.g. as JSON) and checked into a repository.
+#[derive(Clone, PartialEq, Eq, Debug, Default, RustcEncodable, RustcDecodable)]
+pub struct GoldenTrace {
+    pub actions: Vec<Action>,
+    /// The (reward, screen hash) observed after each action.
+    pub steps: Vec<(i32, u64)>,
+}
+
+impl Game {
+    /// Resets the game, plays actions and records the reward and a hash of the
+    /// RGB screen after every one of them. Checking the trace later with
+    /// check_golden detects changes in emulation, e.g. after an ALE upgrade.
+    /// Like demos, traces only reproduce if the ALE is configured the same way.
+    /// The reset never plays reset NOOPs (see set_reset_noops).
+    pub fn record_golden(&mut self, actions: &[Action]) -> GoldenTrace {
+        self.reset_without_noops();
+
+        let mut trace = GoldenTrace::default();
+        for &action in actions {
+            let reward = self.act(action);
+
+            trace.actions.push(action);
+            trace.steps.push((reward, hash_bytes(&self.screen_rgb())));
+        }
+
+        trace
+    }
+
+    /// Resets the game and replays a trace made by record_golden, returning
+    /// the index of the first step whose reward or screen differs. A trace with
+    /// fewer steps than actions diverges at the first missing step. Like
+    /// record_golden, the reset never plays reset NOOPs.
+    pub fn check_golden(&mut self, trace: &GoldenTrace) -> Result<(), usize> {
+        self.reset_without_noops();
+
+        for (i, &action) in trace.actions.iter().enumerate() {
+            let reward = self.act(action);
+
+            match trace.steps.get(i) {
+                Some(&step) if step == (reward, hash_bytes(&self.screen_rgb())) => {}
+                _ => return Err(i),
+            }
+        }
+
+        Ok(())
+    }
+}
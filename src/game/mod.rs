@@ -1,22 +1,198 @@
 use ::libc::c_int;
+use std::cell::Cell;
+use std::collections::{HashSet,VecDeque};
 use std::convert::Into;
-use std::ops::{Deref,DerefMut};
+use std::ops::{Deref,DerefMut,Range};
 use std::ffi::CString;
+use std::fs::{self,File};
+use std::io;
+use std::path::Path;
+use std::ptr;
+use std::sync::mpsc::{Sender,Receiver};
+use std::time::Duration;
 use ::Action;
 use ::Ale;
+use ::AleError;
+use ::{Frame,GrayMethod,Rect,Space};
 
+use ::rand::{Rng,SeedableRng,StdRng};
 use ::rustc_serialize::{Encoder,Encodable,Decoder,Decodable};
 use ::ffi::*;
 
 pub mod serialize;
 
+mod cache;
+mod checkpoint;
+mod demo;
+mod eval;
+mod golden;
+#[cfg(feature="image")]
+mod export;
+mod motion;
+mod reward;
+mod spec;
 mod state;
-pub use self::state::{AleState,AleSystemState};
+#[cfg(feature="ndarray")]
+mod tensor;
+pub use self::eval::EvalReport;
+pub use self::golden::GoldenTrace;
+pub use self::reward::{discounted_return,discounted_returns};
+pub use self::state::{AleState,AleSystemState,CompressedState};
 use self::state::protected::Protected;
+use self::cache::ObsCache;
+use self::checkpoint::Checkpointer;
 
 pub struct Game {
     ale: Ale,
     rom_path: String,
+    // Grayscale frames seen after each act, oldest first. Only recorded
+    // once something asks for a history (frame_history_len > 0).
+    frame_history: VecDeque<Vec<u8>>,
+    frame_history_len: usize,
+    // Set when a state is restored: the screen still shows the old state
+    // until the next act or reset redraws it, so it mustn't be recorded.
+    screen_stale: bool,
+    track_rewards: bool,
+    reward_history: Vec<i32>,
+    act_repeat: usize,
+    obs_cache: Option<ObsCache>,
+    // Screens fetched from ALE, see screen_fetches. A Cell since fetching
+    // only needs &self.
+    screen_fetches: Cell<usize>,
+    reward_transform: Option<Box<FnMut(i32) -> i32 + Send>>,
+    resize: Option<(u32, u32)>,
+    checkpointer: Option<Checkpointer>,
+    checkpoint_error: Option<io::Error>,
+    obs_processor: Option<Box<FnMut(Frame) -> Frame + Send>>,
+    minimal_actions: HashSet<Action>,
+    episode_reward: i32,
+    // Hashes of the RAM after each act, oldest first, recorded once is_stuck
+    // has been called (ram_window > 0).
+    ram_hashes: VecDeque<u64>,
+    ram_window: usize,
+    action_space: ActionSpaceKind,
+    obs_kind: ObsKind,
+    initial_state: Option<AleState>,
+    // Used for the crate's own randomness (e.g. random actions), never the emulator's
+    rng: StdRng,
+    fifo: Option<File>,
+    reset_noops: usize,
+    // The highest episode reward so far this episode, and whether the last act raised it
+    best_episode_reward: i32,
+    new_best: bool,
+}
+
+/// The outcome of a single step, see Game::step.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StepResult {
+    pub reward: i32,
+    pub game_over: bool,
+    /// Whether this step raised the episode reward above the highest it had
+    /// been this episode (which starts at 0).
+    pub new_best: bool,
+}
+
+/// Everything needed to take back a step, see Game::step_undoable.
+pub struct UndoToken {
+    state: AleState,
+    frame: Frame,
+    episode_reward: i32,
+    best_episode_reward: i32,
+    new_best: bool,
+    reward_history_len: usize,
+    frame_history: VecDeque<Vec<u8>>,
+    ram_hashes: VecDeque<u64>,
+}
+
+impl UndoToken {
+    /// The screen from before the step. The emulator doesn't redraw the
+    /// screen on undo, so this is what the game looked like at that point.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+}
+
+/// Sends actions to a game running on its own thread, see Game::into_streamer.
+pub type ActionSender = Sender<Action>;
+
+/// Receives the result of every action sent to a streamed game, in order.
+pub type ObsReceiver = Receiver<StepResult>;
+
+/// The screen and RAM captured together by observe_full.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FullObs {
+    pub frame: Frame,
+    pub ram: Vec<u8>,
+}
+
+/// What Game::observe returns.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ObsKind {
+    /// The observation frame as is, RGB unless a processor changes it.
+    Rgb,
+    /// The observation frame converted to grayscale.
+    Grayscale,
+    /// The RAM together with the grayscale observation frame, for agents
+    /// that use both.
+    RamPixel,
+}
+
+impl Default for ObsKind {
+    fn default() -> ObsKind {
+        ObsKind::Rgb
+    }
+}
+
+/// An observation returned by Game::observe, tagged by the ObsKind it was made with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Observation {
+    /// From ObsKind::Rgb and ObsKind::Grayscale.
+    Pixels(Frame),
+    /// From ObsKind::RamPixel, the RAM and the grayscale frame.
+    RamPixel {
+        ram: Vec<u8>,
+        frame: Frame,
+    },
+}
+
+impl Observation {
+    /// Flattens the observation into one buffer. Pixels are laid out as in
+    /// Frame::data. A RamPixel observation is the RAM bytes (128 on the Atari)
+    /// followed by the grayscale frame row by row, one byte per pixel.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Observation::Pixels(frame) => frame.data,
+            Observation::RamPixel { mut ram, frame } => {
+                ram.extend_from_slice(&frame.data);
+                ram
+            }
+        }
+    }
+}
+
+/// Which set of actions Game::action_space describes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ActionSpaceKind {
+    /// The ROM's minimal action set, the actions that do something in this game.
+    Minimal,
+    /// The ROM's legal action set.
+    Legal,
+    /// All 18 joystick actions, in id order, regardless of the ROM.
+    Full18,
+}
+
+impl Default for ActionSpaceKind {
+    fn default() -> ActionSpaceKind {
+        ActionSpaceKind::Minimal
+    }
+}
+
+/// A summary of a ROM's action space, see Game::action_space_info.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ActionSpaceInfo {
+    pub legal_count: usize,
+    pub minimal_count: usize,
+    pub minimal: Vec<Action>,
 }
 
 unsafe impl Send for Game {}
@@ -24,6 +200,49 @@ unsafe impl Sync for Game {}
 
 impl Game {
 
+    // Only wraps the ALE, without calling into it, since the pointer may not
+    // have a ROM loaded yet (see from_raw_ptr). loaded_rom does the rest.
+    fn with_ale(ale: Ale, rom_path: String) -> Game {
+        Game {
+            ale: ale,
+            rom_path: rom_path,
+            frame_history: VecDeque::new(),
+            frame_history_len: 0,
+            screen_stale: false,
+            track_rewards: false,
+            reward_history: Vec::new(),
+            act_repeat: 1,
+            obs_cache: None,
+            screen_fetches: Cell::new(0),
+            reward_transform: None,
+            resize: None,
+            checkpointer: None,
+            checkpoint_error: None,
+            obs_processor: None,
+            minimal_actions: HashSet::new(),
+            episode_reward: 0,
+            ram_hashes: VecDeque::new(),
+            ram_window: 0,
+            action_space: ActionSpaceKind::default(),
+            obs_kind: ObsKind::default(),
+            initial_state: None,
+            rng: StdRng::from_seed(&[0][..]),
+            fifo: None,
+            reset_noops: 0,
+            best_episode_reward: 0,
+            new_best: false,
+        }
+    }
+
+    // Sets up what depends on the loaded ROM and ALE's settings: the crate's
+    // RNG is seeded with random_seed and the minimal action set cached.
+    fn loaded_rom(&mut self) {
+        let seed = self.ale.get_int("random_seed") as usize;
+
+        self.seed_rng(seed);
+        self.minimal_actions = self.minimal_action_set().into_iter().collect();
+    }
+
     /// Changes the game by loading a new ROM. This consumes the current game
     /// and returns a new one with a reference to the same underlying ALE environment.
     pub fn change_game(self, file_name: &str) -> Game {
@@ -35,9 +254,173 @@ impl Game {
             let file_name = CString::new(file_name).unwrap();
             loadROM(self.ale.p, file_name.as_ptr());
         }
+
+        if let Some(ref mut cache) = self.obs_cache {
+            cache.clear();
+        }
+        self.minimal_actions = self.minimal_action_set().into_iter().collect();
+        self.initial_state = None;
+        self.clear_episode();
     }
 
+    /// Performs an action, repeating it act_repeat times (once by default)
+    /// or until the game ends, and returns the summed reward passed through
+    /// the reward transform, if one is set.
     pub fn act(&mut self, action: Action) -> i32 {
+        let reward = unsafe { act_repeated(self.ale.p, action, self.act_repeat) };
+
+        self.finish_act(reward)
+    }
+
+    /// Acts and reports the reward along with whether the game ended.
+    pub fn step(&mut self, action: Action) -> StepResult {
+        let reward = self.act(action);
+
+        StepResult {
+            reward: reward,
+            game_over: self.is_over(),
+            new_best: self.new_best,
+        }
+    }
+
+    /// Steps, also returning a token that undo uses to take the step back.
+    /// The token holds the emulator state, the episode bookkeeping and the
+    /// screen from before the step, so use step when undo isn't needed.
+    /// Keeping a stack of the returned tokens gives multi-level undo.
+    pub fn step_undoable(&mut self, action: Action) -> (StepResult, UndoToken) {
+        let token = UndoToken {
+            state: self.clone_state(),
+            frame: self.frame(),
+            episode_reward: self.episode_reward,
+            best_episode_reward: self.best_episode_reward,
+            new_best: self.new_best,
+            reward_history_len: self.reward_history.len(),
+            frame_history: self.frame_history.clone(),
+            ram_hashes: self.ram_hashes.clone(),
+        };
+        let result = self.step(action);
+
+        (result, token)
+    }
+
+    /// Takes back the step a token was returned for, restoring the emulator
+    /// along with the episode reward, best reward, and the reward, frame and
+    /// RAM histories. Tokens should be undone newest first: the reward
+    /// history is truncated back to its old length, not copied.
+    ///
+    /// The screen isn't part of the emulator state and isn't redrawn until
+    /// the next act, token.frame() holds the screen from before the step.
+    pub fn undo(&mut self, token: &UndoToken) {
+        self.restore_raw(&token.state);
+
+        self.episode_reward = token.episode_reward;
+        self.best_episode_reward = token.best_episode_reward;
+        self.new_best = token.new_best;
+        self.reward_history.truncate(token.reward_history_len);
+        self.frame_history = token.frame_history.clone();
+        self.ram_hashes = token.ram_hashes.clone();
+    }
+
+    /// Moves the game onto a dedicated thread and returns channels to drive
+    /// it: every action sent is stepped and its StepResult sent back, in order.
+    /// The thread exits, dropping the game and its ALE, once the sender is
+    /// dropped or the receiver hangs up.
+    pub fn into_streamer(self) -> (ActionSender, ObsReceiver) {
+        use std::sync::mpsc::channel;
+        use std::thread;
+
+        let (action_tx, action_rx) = channel();
+        let (result_tx, result_rx) = channel();
+
+        thread::spawn(move || {
+            let mut game = self;
+
+            for action in action_rx {
+                if result_tx.send(game.step(action)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (action_tx, result_rx)
+    }
+
+    /// Like act, but runs the emulation on a worker thread and gives up with
+    /// AleError::Timeout if it takes longer than dur. This guards long
+    /// training jobs against ROMs that hang the emulator.
+    ///
+    /// The game is handed back alongside the reward on success. A thread can't
+    /// be killed, so after a timeout the worker may still be inside the emulator
+    /// and the game is consumed instead: its ALE is detached and deliberately
+    /// leaked rather than freed, and since it never stops existing no new ALE
+    /// can be created in this process.
+    pub fn act_with_timeout(mut self, action: Action, dur: Duration) -> Result<(Game, i32), AleError> {
+        use std::sync::mpsc::channel;
+        use std::thread;
+
+        // Raw pointers aren't Send. This game won't touch the ALE until the worker
+        // answers, and never again if it doesn't, so only one thread uses it at a time.
+        let p = self.ale.p as usize;
+        let repeat = self.act_repeat;
+        let (tx, rx) = channel();
+
+        thread::spawn(move || {
+            let reward = unsafe { act_repeated(p as *mut AleInterface, action, repeat) };
+            let _ = tx.send(reward);
+        });
+
+        match rx.recv_timeout(dur) {
+            Ok(reward) => {
+                let reward = self.finish_act(reward);
+                Ok((self, reward))
+            }
+            Err(_) => {
+                // Dropping the game with a null pointer leaves the ALE alone
+                self.ale.p = ptr::null_mut();
+                Err(AleError::Timeout)
+            }
+        }
+    }
+
+    // Everything act does after the emulator has run: the reward transform
+    // and per-act bookkeeping.
+    fn finish_act(&mut self, reward: i32) -> i32 {
+        let mut reward = reward;
+
+        if let Some(ref mut transform) = self.reward_transform {
+            reward = transform(reward);
+        }
+
+        self.episode_reward += reward;
+        self.new_best = self.episode_reward > self.best_episode_reward;
+        if self.new_best {
+            self.best_episode_reward = self.episode_reward;
+        }
+        if self.track_rewards {
+            self.reward_history.push(reward);
+        }
+        self.screen_stale = false;
+        self.record_frame();
+        self.record_ram_hash();
+        self.write_checkpoint();
+        self.write_fifo();
+
+        reward
+    }
+
+    /// Reseeds the random number generator the crate uses for its own
+    /// randomness, such as picking random actions. It starts out seeded with
+    /// ALE's random_seed setting. This doesn't affect the emulator's RNG.
+    pub fn seed_rng(&mut self, seed: usize) {
+        self.rng.reseed(&[seed][..]);
+    }
+
+    /// Performs an action exactly once through the C API's act and returns
+    /// the untouched reward. This skips everything act layers on top: act repeat,
+    /// the reward transform, and bookkeeping like the reward history.
+    pub fn act_raw(&mut self, action: Action) -> i32 {
+        self.screen_stale = false;
+
         unsafe {
             let Action(action) = action;
 
@@ -45,11 +428,128 @@ impl Game {
         }
     }
 
-    pub unsafe fn from_raw_ptr(p: *mut AleInterface) -> Self {
-        Game {
-            ale: Ale::from_raw_ptr(p),
-            rom_path: "".to_owned(),
+    /// Installs a function applied to every reward act returns, for
+    /// reward shaping. It's applied once per act, to the reward already
+    /// summed over any act repeats, and the reward history records the
+    /// transformed value. act_raw still returns raw rewards.
+    pub fn set_reward_transform(&mut self, f: Box<FnMut(i32) -> i32 + Send>) {
+        self.reward_transform = Some(f);
+    }
+
+    /// Removes the reward transform, if any.
+    pub fn clear_reward_transform(&mut self) {
+        self.reward_transform = None;
+    }
+
+    /// Sets ALE's own frame_skip setting, the number of frames the emulator
+    /// advances (repeating the action) for every act, and resets the game.
+    /// ALE only reads frame_skip when a ROM is loaded, so the current ROM is
+    /// reloaded, which ends the episode. A k of 0 is treated as 1.
+    ///
+    /// This and set_act_repeat are two ways of doing the same thing, and using both
+    /// would skip k*n frames per act, so setting either above 1 while the other is above
+    /// 1 is rejected. Prefer this one, it avoids a round trip through the C API
+    /// per skipped frame.
+    ///
+    /// A game made with from_raw_ptr doesn't know its ROM's path and can't
+    /// reload it, so it gets AleError::NoRom and the setting is left alone.
+    pub fn set_frameskip(&mut self, k: usize) -> Result<(), AleError> {
+        let k = if k == 0 { 1 } else { k };
+        if k > 1 && self.act_repeat > 1 {
+            return Err(AleError::ConflictingFrameSkip(k, self.act_repeat));
+        }
+        if self.rom_path.is_empty() {
+            return Err(AleError::NoRom);
         }
+
+        self.ale.set_int("frame_skip", k as i32);
+
+        let path = self.rom_path.clone();
+        self.change_game_in_place(&path);
+
+        Ok(())
+    }
+
+    /// Sets how many times act repeats its action on the Rust side. See
+    /// set_frameskip for why the two can't both be above 1.
+    pub fn set_act_repeat(&mut self, n: usize) -> Result<(), AleError> {
+        let frame_skip = self.ale.get_int("frame_skip") as usize;
+        if n > 1 && frame_skip > 1 {
+            return Err(AleError::ConflictingFrameSkip(frame_skip, n));
+        }
+
+        self.act_repeat = if n == 0 { 1 } else { n };
+
+        Ok(())
+    }
+
+    /// Repeats an action until the screen differs from the screen at the
+    /// start of the call, the game ends, or max_frames actions have been taken.
+    /// Returns the accumulated reward and the number of actions taken.
+    pub fn act_until_change(&mut self, action: Action, max_frames: usize) -> (i32, usize) {
+        let start = self.screen();
+        let mut buf = Vec::<u8>::with_capacity(start.len());
+        let mut reward = 0;
+        let mut frames = 0;
+
+        while frames < max_frames {
+            reward += self.act(action);
+            frames += 1;
+
+            self.screen_in_buf(&mut buf);
+            if buf != start || self.is_over() {
+                break;
+            }
+        }
+
+        (reward, frames)
+    }
+
+    /// Executes a macro-action (an option) by applying each action in turn.
+    /// Returns the total reward and whether the game ended. If the game ends
+    /// partway through, the remaining actions are skipped and the reward
+    /// collected up to that point is returned along with true.
+    pub fn execute_macro(&mut self, actions: &[Action]) -> (i32, bool) {
+        let mut reward = 0;
+
+        for &action in actions {
+            reward += self.act(action);
+
+            if self.is_over() {
+                return (reward, true);
+            }
+        }
+
+        (reward, false)
+    }
+
+    /// Acts, then writes the reward, game over flag and resulting RGB frame
+    /// into a shared buffer for another process to read. Returns the reward,
+    /// or an error if the frame doesn't fit in the buffer, in which case the
+    /// game isn't advanced.
+    #[cfg(unix)]
+    pub fn step_into_shared(&mut self, action: Action, buf: &mut ::SharedObsBuffer) -> Result<i32, AleError> {
+        let (width, height) = self.screen_dimensions();
+        let needed = (width * height * 3) as usize;
+        if needed > buf.capacity() {
+            return Err(AleError::BufferTooSmall(needed, buf.capacity()));
+        }
+
+        let reward = self.act(action);
+        let frame = self.frame();
+
+        try!(buf.write(reward, self.is_over(), &frame));
+
+        Ok(reward)
+    }
+
+    /// Wraps an ALE created elsewhere without calling into it, so the pointer
+    /// needn't have a ROM loaded yet. That also means the minimal action set
+    /// isn't cached (see has_minimal_action), and since ALE's settings aren't
+    /// read, the crate's RNG starts out seeded with 0 rather than random_seed
+    /// (use seed_rng).
+    pub unsafe fn from_raw_ptr(p: *mut AleInterface) -> Self {
+        Game::with_ale(Ale::from_raw_ptr(p), "".to_owned())
     }
 
     /// This reports whether or not the game is over. This is equivalent to the C API wrapper's
@@ -61,11 +561,133 @@ impl Game {
     }
 
     /// Resets the current game. This is equivalent to the C API wrapper's
-    /// reset_game function.
+    /// reset_game function, and also clears the crate's per-episode bookkeeping
+    /// such as the frame history. If set_reset_noops was given a maximum,
+    /// a random number of NOOPs is played first.
     pub fn reset(&mut self) {
         unsafe {
             reset_game(self.ale.p);
         }
+        self.screen_stale = false;
+
+        self.play_reset_noops();
+        self.clear_episode();
+    }
+
+    /// Makes every reset play between 0 and max NOOPs (inclusive, chosen with
+    /// the internal RNG, see seed_rng) so episodes don't all start from the same
+    /// state, like Gym's NoopResetEnv. 0, the default, turns this off. NOOPs
+    /// are played with act_raw, so they don't count towards the episode.
+    ///
+    /// Methods that replay recorded actions from the start of an episode
+    /// (record_golden, check_golden, replay_demo and run_and_save_gif) reset
+    /// without NOOPs, since a varying start would break the replay.
+    pub fn set_reset_noops(&mut self, max: usize) {
+        self.reset_noops = max;
+    }
+
+    // Resets without reset NOOPs, so the episode always starts from the same
+    // state. Used by everything that replays recorded actions.
+    fn reset_without_noops(&mut self) {
+        unsafe {
+            reset_game(self.ale.p);
+        }
+        self.screen_stale = false;
+
+        self.clear_episode();
+    }
+
+    /// Restarts the episode by restoring the state captured right after a
+    /// reset, rather than resetting the emulator. The first call does a full
+    /// reset to capture that state. Reset NOOPs (see set_reset_noops) are
+    /// played after the state is restored.
+    ///
+    /// Every soft reset starts from exactly the same state, so episodes only
+    /// differ through reset NOOPs or sticky actions (repeat_action_probability).
+    /// ALE's random number generator isn't part of the state and keeps
+    /// advancing, so sticky actions don't repeat from one episode to the next.
+    /// With neither, a deterministic policy replays the same episode every time.
+    /// The screen isn't part of the state, so it's only redrawn on the next act,
+    /// and the frame history (see diff_stack) starts again from there.
+    pub fn soft_reset(&mut self) {
+        match self.initial_state {
+            Some(ref state) => unsafe {
+                restoreState(self.ale.p, state.s());
+            },
+            None => unsafe {
+                reset_game(self.ale.p);
+            },
+        }
+        self.screen_stale = self.initial_state.is_some();
+
+        if self.initial_state.is_none() {
+            self.initial_state = Some(self.clone_state());
+        }
+
+        self.play_reset_noops();
+        self.clear_episode();
+    }
+
+    /// Reseeds the crate's RNG (see seed_rng) and soft resets, so the number
+    /// of reset NOOPs played is reproducible. ALE's own RNG only takes its
+    /// random_seed when a ROM is loaded, so sticky actions aren't reseeded.
+    pub fn soft_reset_seeded(&mut self, seed: usize) {
+        self.seed_rng(seed);
+        self.soft_reset();
+    }
+
+    fn play_reset_noops(&mut self) {
+        if self.reset_noops == 0 {
+            return;
+        }
+
+        let noops = self.rng.gen_range(0, self.reset_noops + 1);
+        for _ in 0..noops {
+            self.act_raw(Action(0));
+
+            if self.is_over() {
+                unsafe {
+                    reset_game(self.ale.p);
+                }
+            }
+        }
+    }
+
+    fn clear_episode(&mut self) {
+        self.episode_reward = 0;
+        self.best_episode_reward = 0;
+        self.new_best = false;
+        self.ram_hashes.clear();
+        self.reward_history.clear();
+        self.frame_history.clear();
+        self.record_frame();
+    }
+
+    /// The total reward returned by act since the last reset (or
+    /// reset_episode_reward).
+    pub fn episode_reward(&self) -> i32 {
+        self.episode_reward
+    }
+
+    /// Zeroes the episode reward (and its running best, see StepResult::new_best)
+    /// without touching the emulator, for frameworks that split one game into
+    /// several logical episodes.
+    pub fn reset_episode_reward(&mut self) {
+        self.episode_reward = 0;
+        self.best_episode_reward = 0;
+        self.new_best = false;
+    }
+
+    /// Turns recording of per-act rewards on or off. Rewards recorded so far
+    /// are kept until the next reset.
+    pub fn track_rewards(&mut self, on: bool) {
+        self.track_rewards = on;
+    }
+
+    /// The reward returned by every act this episode while reward tracking
+    /// was on, in order. This is cleared on reset.
+    pub fn reward_history(&self) -> &[i32] {
+        &self.reward_history
     }
 
     pub fn legal_action_set(&self) -> Vec<Action> {
@@ -106,6 +728,83 @@ impl Game {
         }
     }
 
+    /// Chooses which action set action_space, step_index and action_meanings
+    /// use. The default is the minimal set.
+    pub fn set_action_space(&mut self, kind: ActionSpaceKind) {
+        self.action_space = kind;
+    }
+
+    /// The discrete action space, as chosen by set_action_space. Index i of
+    /// the space is the action step_index(i) takes.
+    pub fn action_space(&self) -> Vec<Action> {
+        match self.action_space {
+            ActionSpaceKind::Minimal => self.minimal_action_set(),
+            ActionSpaceKind::Legal => self.legal_action_set(),
+            ActionSpaceKind::Full18 => (0..18).map(Action).collect(),
+        }
+    }
+
+    /// Steps with the action at index i of the action space. Panics if i is
+    /// out of range.
+    pub fn step_index(&mut self, i: usize) -> StepResult {
+        let action = self.action_space()[i];
+        self.step(action)
+    }
+
+    /// The names of the actions in the action space, in order.
+    pub fn action_meanings(&self) -> Vec<&'static str> {
+        self.action_space().iter().map(|a| a.name()).collect()
+    }
+
+    /// Whether an action is in the minimal action set. Unlike searching
+    /// minimal_action_set this doesn't allocate, the set is cached when
+    /// the ROM is loaded.
+    ///
+    /// The exception is a game made with from_raw_ptr, whose pointer may not
+    /// have had a ROM when it was wrapped. It has no cached set and searches
+    /// minimal_action_set instead, which allocates on every call.
+    pub fn has_minimal_action(&self, a: Action) -> bool {
+        if self.minimal_actions.is_empty() {
+            return self.minimal_action_set().contains(&a);
+        }
+
+        self.minimal_actions.contains(&a)
+    }
+
+    /// Summarizes the legal and minimal action sets in one call, handy for
+    /// deciding which of the two to train on.
+    pub fn action_space_info(&self) -> ActionSpaceInfo {
+        let minimal = self.minimal_action_set();
+
+        ActionSpaceInfo {
+            legal_count: unsafe { getLegalActionSize(self.ale.p) as usize },
+            minimal_count: minimal.len(),
+            minimal: minimal,
+        }
+    }
+
+    /// Describes the legal and minimal action sets as JSON for external tools,
+    /// in the form {"legal": [{"id": 0, "name": "NOOP"}, ...], "minimal": [...]}.
+    pub fn action_sets_json(&self) -> String {
+        use std::collections::BTreeMap;
+        use ::rustc_serialize::json::Json;
+
+        fn describe(actions: Vec<Action>) -> Json {
+            Json::Array(actions.into_iter().map(|action| {
+                let mut obj = BTreeMap::new();
+                obj.insert("id".to_owned(), Json::I64(action.0 as i64));
+                obj.insert("name".to_owned(), Json::String(action.name().to_owned()));
+                Json::Object(obj)
+            }).collect())
+        }
+
+        let mut sets = BTreeMap::new();
+        sets.insert("legal".to_owned(), describe(self.legal_action_set()));
+        sets.insert("minimal".to_owned(), describe(self.minimal_action_set()));
+
+        Json::Object(sets).to_string()
+    }
+
     pub fn frame_number(&self) -> i32 {
         unsafe {
             getFrameNumber(self.ale.p)
@@ -144,6 +843,7 @@ impl Game {
 
             getScreen(self.ale.p, buf.as_mut_ptr());
         }
+        self.screen_fetches.set(self.screen_fetches.get() + 1);
     }
 
     pub fn screen(&self) -> Vec<u8> {
@@ -155,6 +855,273 @@ impl Game {
         buf
     }
 
+    /// Returns the RGB screen as a Frame.
+    pub fn frame(&self) -> Frame {
+        let (width, height) = self.screen_dimensions();
+        Frame::new(width as usize, height as usize, 3, self.screen_rgb())
+    }
+
+    /// Returns the RGB screen and the RAM captured from the same emulator state.
+    pub fn observe_full(&self) -> FullObs {
+        FullObs {
+            frame: self.frame(),
+            ram: self.ram(),
+        }
+    }
+
+    /// Returns the current observation, the RGB screen as a Frame resized
+    /// to the target set by set_resize, if any, then passed through the
+    /// observation processor, if one is set.
+    ///
+    /// If the observation cache is enabled, the state's fingerprint is looked up
+    /// first and a cached frame is used without fetching the screen. Computing
+    /// the fingerprint still reads the RAM, so a hit saves the screen fetch and
+    /// copy rather than every call into the C API.
+    pub fn observation(&mut self) -> Frame {
+        let mut frame = self.cached_frame();
+
+        if let Some((width, height)) = self.resize {
+            frame = frame.resize(width as usize, height as usize);
+        }
+
+        match self.obs_processor {
+            Some(ref mut processor) => processor(frame),
+            None => frame,
+        }
+    }
+
+    /// Installs a function every observation is passed through last, for
+    /// custom filters or annotations beyond the built in transforms.
+    pub fn set_obs_processor(&mut self, f: Box<FnMut(Frame) -> Frame + Send>) {
+        self.obs_processor = Some(f);
+    }
+
+    /// Removes the observation processor, if any.
+    pub fn clear_obs_processor(&mut self) {
+        self.obs_processor = None;
+    }
+
+    fn cached_frame(&mut self) -> Frame {
+        if self.obs_cache.is_none() {
+            return self.frame();
+        }
+
+        let key = self.fingerprint();
+        let cached = self.obs_cache.as_mut().and_then(|c| c.get(key).cloned());
+        if let Some(frame) = cached {
+            return frame;
+        }
+
+        let frame = self.frame();
+        if let Some(ref mut cache) = self.obs_cache {
+            cache.insert(key, frame.clone());
+        }
+
+        frame
+    }
+
+    /// Describes what observe returns for the kind set with set_obs_kind,
+    /// taking the resize target into account: (height, width, 3) bytes for Rgb,
+    /// (height, width, 1) for Grayscale, and for RamPixel the flat
+    /// (ram_size + height * width) layout of Observation::into_bytes. An
+    /// observation processor that changes the frame's shape isn't (and can't
+    /// be) reflected here.
+    pub fn observation_space(&self) -> Space {
+        let (width, height) = match self.resize {
+            Some((width, height)) => (width as usize, height as usize),
+            None => {
+                let (width, height) = self.screen_dimensions();
+                (width as usize, height as usize)
+            }
+        };
+
+        let shape = match self.obs_kind {
+            ObsKind::Rgb => vec![height, width, 3],
+            ObsKind::Grayscale => vec![height, width, 1],
+            ObsKind::RamPixel => vec![self.ram_size() as usize + height * width],
+        };
+
+        Space {
+            shape: shape,
+            low: 0,
+            high: 255,
+            dtype: "uint8",
+        }
+    }
+
+    /// Sets what observe returns.
+    pub fn set_obs_kind(&mut self, kind: ObsKind) {
+        self.obs_kind = kind;
+    }
+
+    /// Returns the current observation in the form chosen with set_obs_kind.
+    /// The pixels come from observation, so resizing, processing and caching
+    /// apply as usual. Frames that don't have 3 channels are never converted
+    /// to grayscale.
+    pub fn observe(&mut self) -> Observation {
+        let frame = self.observation();
+
+        match self.obs_kind {
+            ObsKind::Rgb => Observation::Pixels(frame),
+            ObsKind::Grayscale => Observation::Pixels(gray_frame(frame)),
+            ObsKind::RamPixel => Observation::RamPixel {
+                ram: self.ram(),
+                frame: gray_frame(frame),
+            },
+        }
+    }
+
+    /// Sets a (width,height) that observation resizes every frame to, or
+    /// None to return frames at the native resolution.
+    pub fn set_resize(&mut self, target: Option<(u32, u32)>) {
+        self.resize = target;
+    }
+
+    /// Enables caching of observations keyed by state fingerprint, keeping
+    /// up to capacity of the most recently used ones. This pays off for search
+    /// that keeps revisiting the same states. A capacity of 0 disables the cache.
+    pub fn enable_obs_cache(&mut self, capacity: usize) {
+        self.obs_cache = if capacity == 0 { None } else { Some(ObsCache::new(capacity)) };
+    }
+
+    /// The observation cache's (hits, misses) since it was enabled, or None
+    /// if it isn't.
+    pub fn obs_cache_stats(&self) -> Option<(usize, usize)> {
+        self.obs_cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// How many times the screen (RGB or palette indices) has been fetched
+    /// from ALE by this game, to check what the observation cache saves.
+    pub fn screen_fetches(&self) -> usize {
+        self.screen_fetches.get()
+    }
+
+    /// A cheap fingerprint of the current state, computed by hashing the RAM.
+    /// The Atari's 128 bytes of RAM determine nearly everything about a state,
+    /// but not quite everything (e.g. CPU registers), so treat equal fingerprints
+    /// as "almost certainly the same state".
+    pub fn fingerprint(&self) -> u64 {
+        hash_bytes(&self.ram())
+    }
+
+    /// Returns the screen converted to grayscale by luminance, one byte per pixel.
+    pub fn screen_grayscale(&self) -> Vec<u8> {
+        ::frame::grayscale(&self.screen_rgb())
+    }
+
+    /// Returns the screen converted to grayscale with the given method.
+    pub fn screen_grayscale_with(&self, method: GrayMethod) -> Vec<u8> {
+        ::frame::grayscale_with(&self.screen_rgb(), method)
+    }
+
+    /// Returns a stack of n frame differences, oldest layer first. Each layer
+    /// is the signed difference (newer - older) between two consecutive
+    /// grayscale frames, so motion shows up directly.
+    ///
+    /// This relies on the game recording a history of frames after each act, which
+    /// starts the first time this is called; layers that don't have enough
+    /// history behind them yet are all zero. The history grows to n + 1 frames
+    /// if it's shorter, see set_frame_history_len to shrink or stop it.
+    pub fn diff_stack(&mut self, n: usize) -> Vec<i16> {
+        self.keep_frames(n + 1);
+
+        let (width, height) = self.screen_dimensions();
+        diff_layers(&self.frame_history, n, (width * height) as usize)
+    }
+
+    /// Sets how many grayscale frames of history are kept for diff_stack,
+    /// motion_bbox and block_motion, dropping the oldest frames if there are
+    /// more. Those methods grow the history as they need it, and keeping it
+    /// costs a grayscale conversion per act, so 0 turns recording off until
+    /// one of them is called again.
+    ///
+    /// The history is also cleared whenever a state is restored, so frame
+    /// differences never span two unrelated states. The screen isn't redrawn
+    /// by a restore, so recording only starts again with the next act.
+    pub fn set_frame_history_len(&mut self, len: usize) {
+        self.frame_history_len = len;
+
+        while self.frame_history.len() > len {
+            self.frame_history.pop_front();
+        }
+    }
+
+    /// Returns the smallest rectangle containing every pixel that changed
+    /// between the previous frame and the current one, or None if nothing
+    /// changed. Like diff_stack this uses the recorded frame history, so
+    /// the first call starts the recording and returns None.
+    pub fn motion_bbox(&mut self) -> Option<Rect> {
+        self.keep_frames(2);
+
+        let len = self.frame_history.len();
+        if len < 2 {
+            return None;
+        }
+
+        let (width, _) = self.screen_dimensions();
+        let width = width as usize;
+        let (older, newer) = (&self.frame_history[len - 2], &self.frame_history[len - 1]);
+
+        bounding_box(older.iter().zip(newer.iter()).map(|(a, b)| a != b), width)
+    }
+
+    /// Reports whether none of the last window acts changed the RAM, which
+    /// usually means the game is frozen by a bad configuration. The RAM is
+    /// tracked (as a hash) after each act from the first call onwards, and
+    /// window acts are only judged against the RAM from before the first of
+    /// them, so this returns false until window + 1 acts have been seen.
+    pub fn is_stuck(&mut self, window: usize) -> bool {
+        if window + 1 > self.ram_window {
+            self.ram_window = window + 1;
+        }
+
+        let len = self.ram_hashes.len();
+        if window == 0 || len < window + 1 {
+            return false;
+        }
+
+        let newest = self.ram_hashes[len - 1];
+        self.ram_hashes.iter().skip(len - window - 1).all(|&h| h == newest)
+    }
+
+    fn record_ram_hash(&mut self) {
+        if self.ram_window == 0 {
+            return;
+        }
+
+        let hash = self.fingerprint();
+        self.ram_hashes.push_back(hash);
+
+        while self.ram_hashes.len() > self.ram_window {
+            self.ram_hashes.pop_front();
+        }
+    }
+
+    /// Makes sure at least len frames of history are kept, starting the
+    /// history with the current screen if it wasn't being recorded yet.
+    fn keep_frames(&mut self, len: usize) {
+        if len > self.frame_history_len {
+            self.frame_history_len = len;
+        }
+
+        if self.frame_history.is_empty() {
+            self.record_frame();
+        }
+    }
+
+    fn record_frame(&mut self) {
+        if self.frame_history_len == 0 || self.screen_stale {
+            return;
+        }
+
+        let frame = self.screen_grayscale();
+        self.frame_history.push_back(frame);
+
+        while self.frame_history.len() > self.frame_history_len {
+            self.frame_history.pop_front();
+        }
+    }
+
     pub fn screen_rgb_in_buf(&self, buf: &mut Vec<u8>) {
         unsafe {
             let (width, height) = self.screen_dimensions();
@@ -167,6 +1134,14 @@ impl Game {
 
             getScreenRGB(self.ale.p, buf.as_mut_ptr());
         }
+        self.screen_fetches.set(self.screen_fetches.get() + 1);
+    }
+
+    /// Returns the RGB screen with its channels split into planes, all the
+    /// red values followed by all the green then all the blue. See interleave_rgb
+    /// for the inverse.
+    pub fn screen_rgb_planar(&self) -> Vec<u8> {
+        ::frame::planar_rgb(&self.screen_rgb())
     }
 
     pub fn screen_rgb(&self) -> Vec<u8> {
@@ -184,6 +1159,11 @@ impl Game {
         }
     }
 
+    /// The range of valid RAM addresses, 0..ram_size().
+    pub fn ram_range(&self) -> Range<usize> {
+        0..self.ram_size() as usize
+    }
+
     pub fn ram_in_buf(&self, buf: &mut Vec<u8>) {
         unsafe {
             let size = self.ram_size() as usize;
@@ -213,10 +1193,14 @@ impl Game {
         }
     }
 
+    /// Restores the state saved by save_state. Like restore_from_cloned_state,
+    /// this clears the frame history.
     pub fn load_state(&mut self) {
         unsafe {
             loadState(self.ale.p);
         }
+        self.screen_stale = true;
+        self.frame_history.clear();
     }
 
     pub fn save_screen_png(&self, file_name: &str) {
@@ -235,16 +1219,251 @@ impl Game {
         unsafe { AleSystemState::new(cloneSystemState(self.ale.p)) }
     }
 
+    /// Clones the state like clone_state, then serializes and compresses
+    /// it. This trades CPU time for memory when keeping large pools of states,
+    /// e.g. in tree search.
+    pub fn clone_state_compressed(&self) -> CompressedState {
+        let state = self.clone_state();
+        state::compress_state(state.s())
+    }
+
+    /// Restores a state made by clone_state_compressed.
+    pub fn restore_from_compressed(&mut self, s: &CompressedState) {
+        let state = AleState::new(state::decompress_state(s));
+        self.restore_from_cloned_state(&state);
+    }
+
+    /// Restores a state made by clone_state. This clears the frame history.
     pub fn restore_from_cloned_state(&mut self, s: &AleState) {
+        self.restore_raw(s);
+        self.frame_history.clear();
+    }
+
+    /// Restores a state made by clone_system_state. This clears the frame history.
+    pub fn restore_from_cloned_system_state(&mut self, s: &AleSystemState) {
+        self.restore_system_raw(s);
+        self.frame_history.clear();
+    }
+
+    // Restores a state without touching the crate's bookkeeping, for probes
+    // that put the game back the way they found it.
+    fn restore_raw(&mut self, s: &AleState) {
         unsafe {
             restoreState(self.ale.p, s.s());
         }
+        self.screen_stale = true;
     }
 
-    pub fn restore_from_cloned_system_state(&mut self, s: &AleSystemState) {
+    fn restore_system_raw(&mut self, s: &AleSystemState) {
         unsafe {
             restoreSystemState(self.ale.p, s.s());
         }
+        self.screen_stale = true;
+    }
+
+    /// Clones the system state and serializes it, freeing the C copy right
+    /// away. Useful when states are kept on disk rather than in memory, since
+    /// nothing but the returned bytes stays alive. Restore with restore_state_bytes.
+    pub fn save_state_bytes_and_free(&self) -> Vec<u8> {
+        let state = self.clone_system_state();
+        state::encode_state(state.s()).into_iter().map(|b| b as u8).collect()
+    }
+
+    /// Restores a system state serialized by save_state_bytes_and_free.
+    /// Like any restored state, the screen isn't redrawn until the next act.
+    pub fn restore_state_bytes(&mut self, bytes: &[u8]) {
+        let serial: Vec<i8> = bytes.iter().map(|&b| b as i8).collect();
+        let state = AleSystemState::new(state::decode_state(&serial));
+        self.restore_from_cloned_system_state(&state);
+    }
+
+    /// Checks that the ALE this game runs on behaves sanely: the legal action
+    /// set isn't empty, and restoring a cloned system state and replaying
+    /// the same action reproduces the same screen. A broken build or a
+    /// mismatched library version tends to fail this, so it's worth calling
+    /// right after loading a ROM. The game's state is restored afterwards.
+    pub fn self_check(&mut self) -> Result<(), AleError> {
+        if self.legal_action_set().is_empty() {
+            return Err(AleError::SelfCheck("the legal action set is empty"));
+        }
+
+        let original = self.clone_system_state();
+
+        self.act_raw(Action(0));
+        let first = self.screen_rgb();
+
+        self.restore_system_raw(&original);
+        self.act_raw(Action(0));
+        let second = self.screen_rgb();
+
+        self.restore_system_raw(&original);
+
+        if first != second {
+            return Err(AleError::SelfCheck("restoring a cloned state didn't reproduce the same frame"));
+        }
+
+        Ok(())
+    }
+
+    /// Probes how many emulator frames the game survives while repeating
+    /// action, giving up after max frames. Returns None if it's still alive
+    /// by then. The game is put back afterwards, RNG included: with sticky
+    /// actions the probe's acts draw random numbers, and later acts would
+    /// otherwise see a different sequence.
+    pub fn frames_to_death(&mut self, action: Action, max: usize) -> Option<usize> {
+        let original = self.clone_system_state();
+        let start = self.frame_number();
+        let mut result = None;
+
+        loop {
+            let frames = (self.frame_number() - start) as usize;
+
+            if self.is_over() {
+                result = Some(frames);
+                break;
+            }
+            if frames >= max {
+                break;
+            }
+
+            self.act_raw(action);
+        }
+
+        self.restore_system_raw(&original);
+
+        result
+    }
+
+    /// Renders the difference between two states as an RGB frame holding the
+    /// absolute per-channel difference of their screens, so identical
+    /// regions are black.
+    ///
+    /// The screen isn't part of a cloned state, so each state is restored and
+    /// advanced by a single NOOP frame before its screen is captured. Those
+    /// two frames also draw on the emulator's RNG, so the whole system state
+    /// is put back afterwards rather than just the cloned state.
+    pub fn diff_states(&mut self, a: &AleState, b: &AleState) -> Frame {
+        let original = self.clone_system_state();
+
+        self.restore_raw(a);
+        self.act_raw(Action(0));
+        let frame_a = self.frame();
+
+        self.restore_raw(b);
+        self.act_raw(Action(0));
+        let frame_b = self.frame();
+
+        self.restore_system_raw(&original);
+
+        let data = frame_a.data.iter()
+                               .zip(frame_b.data.iter())
+                               .map(|(&x, &y)| if x > y { x - y } else { y - x })
+                               .collect();
+
+        Frame::new(frame_a.width, frame_a.height, frame_a.channels, data)
+    }
+
+    /// Writes a system state checkpoint into dir every n_frames emulator
+    /// frames, checked after every act, so a crashed run can be resumed with
+    /// load_checkpoint. Checkpoints rotate through a few files named
+    /// checkpoint-<i>.state, the newest being the most recently modified.
+    /// An n_frames of 0 or less turns checkpointing off.
+    ///
+    /// dir is created if it doesn't exist. Each checkpoint is written to a
+    /// temporary file and renamed into place, so a crash mid-write never
+    /// leaves a truncated checkpoint behind. If a write fails anyway, act
+    /// carries on: checkpointing is turned off and the error is kept for
+    /// checkpoint_error.
+    pub fn checkpoint_every(&mut self, n_frames: i32, dir: &Path) -> io::Result<()> {
+        self.checkpoint_error = None;
+        self.checkpointer = None;
+
+        if n_frames > 0 {
+            try!(fs::create_dir_all(dir));
+            self.checkpointer = Some(Checkpointer::new(n_frames, dir, self.frame_number()));
+        }
+
+        Ok(())
+    }
+
+    /// The error that turned periodic checkpointing off, if a write failed.
+    pub fn checkpoint_error(&self) -> Option<&io::Error> {
+        self.checkpoint_error.as_ref()
+    }
+
+    /// Restores a system state checkpoint written by checkpoint_every.
+    pub fn load_checkpoint<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        use std::io::Read;
+
+        let mut bytes = Vec::<u8>::new();
+        try!(try!(File::open(path)).read_to_end(&mut bytes));
+
+        let serial: Vec<i8> = bytes.into_iter().map(|b| b as i8).collect();
+        let state = AleSystemState::new(state::decode_state(&serial));
+        self.restore_from_cloned_system_state(&state);
+
+        Ok(())
+    }
+
+    fn write_checkpoint(&mut self) {
+        let frame = self.frame_number();
+        let path = match self.checkpointer.as_mut().and_then(|c| c.due(frame)) {
+            Some(path) => path,
+            None => return,
+        };
+
+        let state = self.clone_system_state();
+        let bytes: Vec<u8> = state::encode_state(state.s()).into_iter().map(|b| b as u8).collect();
+
+        if let Err(err) = checkpoint::write_atomically(&path, &bytes) {
+            self.checkpointer = None;
+            self.checkpoint_error = Some(err);
+        }
+    }
+
+    /// Streams every frame to path (typically a named pipe) after each act,
+    /// for live viewing by an external process. Each frame is a 12 byte header
+    /// of width, height and frame number as little endian u32s, followed by
+    /// the RGB pixels.
+    ///
+    /// Opening a FIFO for writing blocks until a reader opens it. A regular file
+    /// works too and is truncated first, so it holds only this stream. If writing
+    /// fails, e.g. because the reader went away, the stream is detached
+    /// rather than interrupting the game.
+    pub fn attach_fifo(&mut self, path: &Path) -> io::Result<()> {
+        use std::fs::OpenOptions;
+
+        self.fifo = Some(try!(OpenOptions::new().write(true).create(true).truncate(true).open(path)));
+
+        Ok(())
+    }
+
+    /// Stops streaming frames.
+    pub fn detach_fifo(&mut self) {
+        self.fifo = None;
+    }
+
+    fn write_fifo(&mut self) {
+        use std::io::Write;
+
+        if self.fifo.is_none() {
+            return;
+        }
+
+        let (width, height) = self.screen_dimensions();
+        let mut buf = Vec::<u8>::with_capacity(12 + (width * height * 3) as usize);
+        for &word in &[width as u32, height as u32, self.frame_number() as u32] {
+            buf.extend_from_slice(&[word as u8, (word >> 8) as u8, (word >> 16) as u8, (word >> 24) as u8]);
+        }
+        buf.extend_from_slice(&self.screen_rgb());
+
+        let failed = match self.fifo {
+            Some(ref mut fifo) => fifo.write_all(&buf).is_err(),
+            None => false,
+        };
+        if failed {
+            self.fifo = None;
+        }
     }
 
     pub fn rom(&self) -> serialize::Rom {
@@ -252,6 +1471,71 @@ impl Game {
     }
 }
 
+// Acts repeat times or until the game ends, returning the summed reward.
+unsafe fn act_repeated(p: *mut AleInterface, action: Action, repeat: usize) -> i32 {
+    let Action(action) = action;
+    let mut reward = 0;
+
+    for _ in 0..repeat {
+        reward += act(p, action);
+
+        if game_over(p) != 0 {
+            break;
+        }
+    }
+
+    reward
+}
+
+// Stacks the n most recent differences between consecutive frames of size
+// pixels, oldest first, leaving layers without two frames behind them zero.
+fn diff_layers(frames: &VecDeque<Vec<u8>>, n: usize, size: usize) -> Vec<i16> {
+    use std::cmp::min;
+
+    let mut stack = vec![0i16; n * size];
+    let available = min(frames.len().saturating_sub(1), n);
+
+    for i in 0..available {
+        let newer = &frames[frames.len() - 1 - i];
+        let older = &frames[frames.len() - 2 - i];
+        let layer = &mut stack[(n - 1 - i) * size..(n - i) * size];
+
+        for ((out, &new), &old) in layer.iter_mut().zip(newer.iter()).zip(older.iter()) {
+            *out = new as i16 - old as i16;
+        }
+    }
+
+    stack
+}
+
+// The smallest rectangle holding every marked pixel of a row-major image
+// width pixels wide, or None if none are marked.
+fn bounding_box<I: Iterator<Item=bool>>(marked: I, width: usize) -> Option<Rect> {
+    use std::cmp::{min,max};
+
+    let mut bbox: Option<(usize, usize, usize, usize)> = None;
+    for (i, _) in marked.enumerate().filter(|&(_, m)| m) {
+        let (x, y) = (i % width, i / width);
+        bbox = Some(match bbox {
+            None => (x, y, x, y),
+            Some((x0, y0, x1, y1)) => (min(x0, x), min(y0, y), max(x1, x), max(y1, y)),
+        });
+    }
+
+    bbox.map(|(x0, y0, x1, y1)| Rect { x: x0, y: y0, width: x1 - x0 + 1, height: y1 - y0 + 1 })
+}
+
+// Converts an RGB frame to grayscale, leaving any other frame alone.
+fn gray_frame(frame: Frame) -> Frame {
+    if frame.is_rgb() { frame.to_grayscale() } else { frame }
+}
+
+// FNV-1a, used rather than the std hasher because fingerprints and frame
+// hashes need to be stable across Rust versions and runs.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
 impl Encodable for Game {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
         use self::serialize::Rom;
@@ -303,7 +1587,55 @@ pub mod protected {
 
     impl Protected for Game {
         fn new(ale: Ale, path: String) -> Self {
-            Game { ale: ale, rom_path: path }
+            let mut game = Game::with_ale(ale, path);
+            game.loaded_rom();
+            game
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use ::Rect;
+    use super::{bounding_box,diff_layers};
+
+    #[test]
+    fn diff_layers_show_a_moving_pixel() {
+        // A 4x1 strip with a bright pixel moving one step right per frame
+        let frames: VecDeque<Vec<u8>> = vec![
+            vec![200, 0, 0, 0],
+            vec![0, 200, 0, 0],
+            vec![0, 0, 200, 0],
+        ].into_iter().collect();
+
+        let stack = diff_layers(&frames, 2, 4);
+
+        assert_eq!(&stack[..4], &[-200, 200, 0, 0]);
+        assert_eq!(&stack[4..], &[0, -200, 200, 0]);
+    }
+
+    #[test]
+    fn diff_layers_without_history_are_zero() {
+        let frames: VecDeque<Vec<u8>> = vec![vec![1, 2, 3, 4]].into_iter().collect();
+
+        assert_eq!(diff_layers(&frames, 2, 4), vec![0; 8]);
+    }
+
+    #[test]
+    fn bounding_box_of_a_single_pixel_is_one_by_one() {
+        let older = [0u8, 0, 0, 0, 0, 0];
+        let newer = [0u8, 0, 0, 0, 9, 0];
+        let marked = older.iter().zip(newer.iter()).map(|(a, b)| a != b);
+
+        assert_eq!(bounding_box(marked, 3), Some(Rect { x: 1, y: 1, width: 1, height: 1 }));
+    }
+
+    #[test]
+    fn bounding_box_spans_every_marked_pixel() {
+        let marked = vec![false, true, false, false, false, false, true, false];
+
+        assert_eq!(bounding_box(marked.into_iter(), 4), Some(Rect { x: 1, y: 0, width: 2, height: 2 }));
+        assert_eq!(bounding_box(vec![false; 8].into_iter(), 4), None);
+    }
+}
@@ -0,0 +1,221 @@
+use ::ffi::reset_game;
+use ::rand::Rng;
+use ::Rect;
+use super::{Game,bounding_box};
+
+// How many frames after a reward still count as part of the reward event,
+// since many games redraw the score a frame or two late.
+const REWARD_WINDOW: usize = 2;
+
+// How far, in pixels, block_motion searches in each direction.
+const SEARCH_RADIUS: isize = 4;
+
+impl Game {
+    /// Experimental: guesses where the score is drawn by playing sample_frames
+    /// random actions and finding the pixels that change much more often
+    /// around a reward than otherwise. Returns the bounding box of those
+    /// pixels, or None if no reward was seen or nothing stood out.
+    ///
+    /// This is a heuristic aid for games without known RAM layouts, and games
+    /// where rewards always coincide with other big screen changes will fool
+    /// it. The random actions come from a copy of the crate's RNG, and the
+    /// emulator's system state is put back at the end, so running the probe
+    /// leaves both RNGs where they were.
+    pub fn detect_score_region(&mut self, sample_frames: usize) -> Option<Rect> {
+        use std::cmp::max;
+
+        let actions = self.minimal_action_set();
+        if actions.is_empty() {
+            return None;
+        }
+
+        let original = self.clone_system_state();
+        let mut rng = self.rng.clone();
+        let (width, height) = self.screen_dimensions();
+        let (width, height) = (width as usize, height as usize);
+
+        // For every pixel, in how many reward events and in how many other
+        // frames it changed
+        let mut reward_hits = vec![0u32; width * height];
+        let mut other_hits = vec![0u32; width * height];
+        let (mut events, mut others) = (0u32, 0u32);
+
+        let mut event = vec![false; width * height];
+        let mut window = 0;
+        let mut prev = self.screen_grayscale();
+
+        for _ in 0..sample_frames {
+            let action = actions[rng.gen_range(0, actions.len())];
+            let reward = self.act_raw(action);
+            let cur = self.screen_grayscale();
+
+            if reward != 0 && window == 0 {
+                for v in event.iter_mut() {
+                    *v = false;
+                }
+                window = REWARD_WINDOW + 1;
+            }
+
+            if window > 0 {
+                for ((e, a), b) in event.iter_mut().zip(&prev).zip(&cur) {
+                    *e = *e || a != b;
+                }
+
+                window -= 1;
+                if window == 0 {
+                    events += 1;
+                    for (hits, &e) in reward_hits.iter_mut().zip(&event) {
+                        *hits += e as u32;
+                    }
+                }
+            } else {
+                others += 1;
+                for ((hits, a), b) in other_hits.iter_mut().zip(&prev).zip(&cur) {
+                    *hits += (a != b) as u32;
+                }
+            }
+
+            if self.is_over() {
+                // Bypasses reset so the episode's bookkeeping survives the probe
+                unsafe {
+                    reset_game(self.ale.p);
+                }
+                window = 0;
+                prev = self.screen_grayscale();
+            } else {
+                prev = cur;
+            }
+        }
+
+        self.restore_system_raw(&original);
+
+        if events == 0 {
+            return None;
+        }
+
+        let others = max(others, 1) as f32;
+        let marked = reward_hits.iter().zip(&other_hits).map(|(&r, &o)| {
+            (r as f32 / events as f32) - (o as f32 / others) > 0.5
+        });
+
+        bounding_box(marked, width)
+    }
+
+    /// Estimates how each block x block tile of the screen moved between the
+    /// previous frame and the current one, by finding the displacement within
+    /// a few pixels that minimizes the sum of absolute differences of the
+    /// grayscale pixels. Returns one (dx,dy) per tile, row by row, with tiles
+    /// that don't fit fully on the screen left out.
+    ///
+    /// This is a cheap approximation, not optical flow: flat regions
+    /// and motion beyond the search window give meaningless vectors, and ties
+    /// go to no motion. Like diff_stack, the first call only starts recording
+    /// frames, so every vector is (0,0) until the game has acted once more.
+    pub fn block_motion(&mut self, block: usize) -> Vec<(i8, i8)> {
+        if block == 0 {
+            return Vec::new();
+        }
+
+        self.keep_frames(2);
+
+        let (width, height) = self.screen_dimensions();
+        let (width, height) = (width as usize, height as usize);
+        let (cols, rows) = (width / block, height / block);
+
+        let frames = &self.frame_history;
+        if frames.len() < 2 {
+            return vec![(0, 0); cols * rows];
+        }
+
+        block_vectors(&frames[frames.len() - 2], &frames[frames.len() - 1], width, height, block)
+    }
+}
+
+// The block matching behind block_motion, on two grayscale frames of the
+// given size. block must be above 0.
+fn block_vectors(prev: &[u8], cur: &[u8], width: usize, height: usize, block: usize) -> Vec<(i8, i8)> {
+    let (cols, rows) = (width / block, height / block);
+    let offsets = search_offsets();
+    let mut vectors = Vec::<(i8, i8)>::with_capacity(cols * rows);
+
+    for by in 0..rows {
+        for bx in 0..cols {
+            let (x0, y0) = ((bx * block) as isize, (by * block) as isize);
+            let mut best = (0, 0);
+            let mut best_sad = ::std::u32::MAX;
+
+            // No motion is tried first, so it wins ties
+            for &dy in &offsets {
+                for &dx in &offsets {
+                    let (px, py) = (x0 - dx, y0 - dy);
+                    if px < 0 || py < 0 || px as usize + block > width || py as usize + block > height {
+                        continue;
+                    }
+
+                    let mut sad = 0u32;
+                    for row in 0..block {
+                        let c = (y0 as usize + row) * width + x0 as usize;
+                        let p = (py as usize + row) * width + px as usize;
+                        for (&a, &b) in cur[c..c + block].iter().zip(&prev[p..p + block]) {
+                            sad += (a as i32 - b as i32).abs() as u32;
+                        }
+                    }
+
+                    if sad < best_sad {
+                        best_sad = sad;
+                        best = (dx as i8, dy as i8);
+                    }
+                }
+            }
+
+            vectors.push(best);
+        }
+    }
+
+    vectors
+}
+
+// 0, 1, -1, 2, -2, ... up to SEARCH_RADIUS
+fn search_offsets() -> Vec<isize> {
+    let mut offsets = vec![0];
+    for d in 1..SEARCH_RADIUS + 1 {
+        offsets.push(d);
+        offsets.push(-d);
+    }
+
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_vectors;
+
+    // A 24x24 frame, black but for a 4x4 square of distinct values at (x,y)
+    fn square_at(x: usize, y: usize) -> Vec<u8> {
+        let mut frame = vec![0u8; 24 * 24];
+        for row in 0..4 {
+            for col in 0..4 {
+                frame[(y + row) * 24 + x + col] = 100 + (row * 4 + col) as u8 * 10;
+            }
+        }
+        frame
+    }
+
+    #[test]
+    fn block_vectors_follow_a_shifted_square() {
+        let vectors = block_vectors(&square_at(9, 9), &square_at(11, 10), 24, 24, 8);
+
+        assert_eq!(vectors.len(), 9);
+        assert_eq!(vectors[4], (2, 1));
+        for (i, &v) in vectors.iter().enumerate().filter(|&(i, _)| i != 4) {
+            assert_eq!(v, (0, 0), "tile {}", i);
+        }
+    }
+
+    #[test]
+    fn block_vectors_leave_out_partial_tiles() {
+        let frame = vec![0u8; 20 * 10];
+
+        assert_eq!(block_vectors(&frame, &frame, 20, 10, 8), vec![(0, 0), (0, 0)]);
+    }
+}
@@ -0,0 +1,46 @@
+/// Computes the discounted return of a reward sequence,
+/// r_0 + gamma*r_1 + gamma^2*r_2 + ...
+pub fn discounted_return(rewards: &[i32], gamma: f32) -> f32 {
+    rewards.iter().rev().fold(0.0, |acc, &r| r as f32 + gamma * acc)
+}
+
+/// Computes the discounted return from every timestep onward, so the
+/// first element is the return of the whole sequence and the last
+/// is just the final reward.
+pub fn discounted_returns(rewards: &[i32], gamma: f32) -> Vec<f32> {
+    let mut returns = vec![0.0; rewards.len()];
+    let mut acc = 0.0;
+
+    for (i, &r) in rewards.iter().enumerate().rev() {
+        acc = r as f32 + gamma * acc;
+        returns[i] = acc;
+    }
+
+    returns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn discounted_return_matches_hand_computed() {
+        // 1 + 0.99*0 + 0.99^2*2
+        assert_close(discounted_return(&[1, 0, 2], 0.99), 2.9602);
+        assert_close(discounted_return(&[], 0.99), 0.0);
+    }
+
+    #[test]
+    fn discounted_returns_match_hand_computed() {
+        let returns = discounted_returns(&[1, 0, 2], 0.99);
+
+        assert_eq!(returns.len(), 3);
+        assert_close(returns[0], 2.9602);
+        assert_close(returns[1], 1.98);
+        assert_close(returns[2], 2.0);
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self,Read,Write};
+use std::path::Path;
+use ::rustc_serialize::json::Json;
+use super::{Game,hash_bytes};
+
+// The ALE settings recorded in an env spec, by type.
+const INT_SETTINGS: &'static [&'static str] = &["random_seed", "frame_skip", "max_num_frames_per_episode"];
+const FLOAT_SETTINGS: &'static [&'static str] = &["repeat_action_probability"];
+const BOOL_SETTINGS: &'static [&'static str] = &["color_averaging"];
+
+impl Game {
+    /// Writes a JSON description of this environment to path: a checksum of
+    /// the ROM's contents, the action space, the observation space, and the
+    /// ALE settings that change how the game plays (random_seed, frame_skip,
+    /// max_num_frames_per_episode, repeat_action_probability and color_averaging)
+    /// along with the crate's act repeat. Check a game against it with validate_env_spec.
+    pub fn export_env_spec(&self, path: &Path) -> io::Result<()> {
+        let spec = try!(self.env_spec());
+
+        let mut file = try!(File::create(path));
+        try!(file.write_all(spec.pretty().to_string().as_bytes()));
+
+        Ok(())
+    }
+
+    /// Whether this game matches the spec written to path by export_env_spec.
+    pub fn validate_env_spec(&self, path: &Path) -> io::Result<bool> {
+        let mut file = try!(File::open(path));
+        let mut contents = String::new();
+        try!(file.read_to_string(&mut contents));
+
+        let saved = try!(Json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)));
+        // Round trip our own spec so numbers are typed the same way as the parsed ones
+        let current = Json::from_str(&try!(self.env_spec()).to_string()).expect("env spec is valid JSON");
+
+        Ok(saved == current)
+    }
+
+    fn env_spec(&self) -> io::Result<Json> {
+        let mut rom = Vec::<u8>::new();
+        try!(try!(File::open(&self.rom_path)).read_to_end(&mut rom));
+
+        let actions = self.action_space().into_iter().map(|action| {
+            let mut obj = BTreeMap::new();
+            obj.insert("id".to_owned(), Json::I64(action.0 as i64));
+            obj.insert("name".to_owned(), Json::String(action.name().to_owned()));
+            Json::Object(obj)
+        }).collect();
+
+        let mut settings = BTreeMap::new();
+        for &key in INT_SETTINGS {
+            settings.insert(key.to_owned(), Json::I64(self.get_int(key) as i64));
+        }
+        for &key in FLOAT_SETTINGS {
+            settings.insert(key.to_owned(), Json::F64(self.get_float(key) as f64));
+        }
+        for &key in BOOL_SETTINGS {
+            settings.insert(key.to_owned(), Json::Boolean(self.get_bool(key)));
+        }
+        settings.insert("act_repeat".to_owned(), Json::U64(self.act_repeat as u64));
+
+        let mut spec = BTreeMap::new();
+        spec.insert("rom_checksum".to_owned(), Json::String(format!("{:016x}", hash_bytes(&rom))));
+        spec.insert("action_space".to_owned(), Json::Array(actions));
+        spec.insert("observation_space".to_owned(),
+                    Json::from_str(&self.observation_space().to_json()).expect("space is valid JSON"));
+        spec.insert("settings".to_owned(), Json::Object(settings));
+
+        Ok(Json::Object(spec))
+    }
+}
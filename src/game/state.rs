@@ -60,7 +60,56 @@ impl Decodable for AleSystemState {
     }
 }
 
-fn encode_state(s: *mut CAleState) -> Vec<i8> {
+/// A state stored zlib compressed, see Game::clone_state_compressed.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CompressedState {
+    bytes: Vec<u8>,
+}
+
+impl CompressedState {
+    /// The size of the compressed state in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the compressed state holds no bytes, which never happens for
+    /// one made by Game::clone_state_compressed.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+// Compressing into a Vec can't fail (short of running out of memory, which
+// aborts anyway), so the expects below never fire.
+pub fn compress_state(s: *mut CAleState) -> CompressedState {
+    use std::io::Write;
+    use ::flate2::Compression;
+    use ::flate2::write::ZlibEncoder;
+
+    let serial: Vec<u8> = encode_state(s).into_iter().map(|b| b as u8).collect();
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::Default);
+    encoder.write_all(&serial).expect("Could not compress state");
+
+    CompressedState {
+        bytes: encoder.finish().expect("Could not compress state"),
+    }
+}
+
+// The caller owns the returned state. The bytes are private and only ever
+// come from compress_state, so they always decompress and the expect never fires.
+pub fn decompress_state(compressed: &CompressedState) -> *mut CAleState {
+    use std::io::Read;
+    use ::flate2::read::ZlibDecoder;
+
+    let mut serial = Vec::<u8>::new();
+    ZlibDecoder::new(&compressed.bytes[..]).read_to_end(&mut serial).expect("Could not decompress state");
+
+    let serial: Vec<i8> = serial.into_iter().map(|b| b as i8).collect();
+    decode_state(&serial)
+}
+
+pub fn encode_state(s: *mut CAleState) -> Vec<i8> {
     unsafe {
         let len = encodeStateLen(s) as usize;
         let mut buf = Vec::<i8>::with_capacity(len);
@@ -71,7 +120,7 @@ fn encode_state(s: *mut CAleState) -> Vec<i8> {
     }
 }
 
-fn decode_state(serialized: &Vec<i8>) -> *mut CAleState {
+pub fn decode_state(serialized: &Vec<i8>) -> *mut CAleState {
     unsafe {
         decodeState(serialized.as_ptr(), serialized.len() as c_int)
     }
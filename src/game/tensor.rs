@@ -0,0 +1,17 @@
+use ::ndarray::Array3;
+use super::Game;
+
+impl Game {
+    /// Returns the RGB screen as a float tensor in CHW layout: shape
+    /// (3, height, width), channels in RGB order, values scaled to [0,1].
+    /// This is the layout most PyTorch-style convolutional models expect.
+    pub fn obs_tensor(&self) -> Array3<f32> {
+        let (width, height) = self.screen_dimensions();
+        let (width, height) = (width as usize, height as usize);
+        let planar = ::frame::planar_rgb(&self.screen_rgb());
+
+        let data = planar.into_iter().map(|v| v as f32 / 255.0).collect();
+
+        Array3::from_shape_vec((3, height, width), data).expect("planar buffer has the wrong size")
+    }
+}
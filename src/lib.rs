@@ -1,12 +1,38 @@
 #![cfg_attr(feature="use_clippy", feature(plugin))]
 #![cfg_attr(feature="use_clippy", plugin(clippy))]
+extern crate flate2;
 extern crate rustc_serialize;
 extern crate libc;
+extern crate rand;
+#[cfg(feature="image")]
+extern crate gif;
+#[cfg(feature="image")]
+extern crate png;
+#[cfg(feature="ndarray")]
+extern crate ndarray;
 
+pub mod compat;
+mod demo;
 pub mod ffi;
+mod error;
+mod font;
+mod frame;
 mod game;
+mod policy;
 pub mod serialize;
-pub use self::game::{Game,AleState,AleSystemState};
+mod space;
+mod stats;
+#[cfg(unix)]
+mod shared;
+pub use self::demo::DemoRecorder;
+pub use self::error::AleError;
+pub use self::frame::{Frame,Rect,GrayMethod,crop_preset,interleave_rgb};
+#[cfg(unix)]
+pub use self::shared::{SharedObsBuffer,SharedObs};
+pub use self::policy::{sample_action,argmax_action};
+pub use self::space::Space;
+pub use self::stats::RunningMeanStd;
+pub use self::game::{Game,StepResult,ActionSpaceKind,ActionSender,ObsReceiver,UndoToken,FullObs,ObsKind,Observation,ActionSpaceInfo,AleState,AleSystemState,CompressedState,EvalReport,GoldenTrace,discounted_return,discounted_returns};
 
 use ::ffi::*;
 use ::libc::c_int;
@@ -18,6 +44,26 @@ use std::sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT};
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub struct Action(pub i32);
 
+const ACTION_NAMES: [&'static str; 18] = [
+    "NOOP", "FIRE", "UP", "RIGHT", "LEFT", "DOWN",
+    "UPRIGHT", "UPLEFT", "DOWNRIGHT", "DOWNLEFT",
+    "UPFIRE", "RIGHTFIRE", "LEFTFIRE", "DOWNFIRE",
+    "UPRIGHTFIRE", "UPLEFTFIRE", "DOWNRIGHTFIRE", "DOWNLEFTFIRE",
+];
+
+impl Action {
+    /// The name ALE gives this action (without the PLAYER_A_ prefix),
+    /// e.g. "NOOP" or "UPFIRE", or "UNKNOWN" for ids outside the 18 joystick actions.
+    pub fn name(&self) -> &'static str {
+        let Action(id) = *self;
+        if id >= 0 && (id as usize) < ACTION_NAMES.len() {
+            ACTION_NAMES[id as usize]
+        } else {
+            "UNKNOWN"
+        }
+    }
+}
+
 pub struct Ale {
     p: *mut AleInterface
 }
@@ -29,6 +75,50 @@ The ALE currently uses global statics and is not thread safe, if you need multip
 If you need to run multiple ALEs in sequence on separate threads, arrange the synchronization yourself (e.g. mutexes or sending over a channel).
 "#;
 
+/// Lists the optional cargo features this crate was compiled with,
+/// useful for confirming a build configuration in bug reports.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+
+    if cfg!(feature = "use_clippy") {
+        features.push("use_clippy");
+    }
+    if cfg!(feature = "test-reset") {
+        features.push("test-reset");
+    }
+    if cfg!(feature = "image") {
+        features.push("image");
+    }
+    if cfg!(feature = "ndarray") {
+        features.push("ndarray");
+    }
+
+    features
+}
+
+/// Reports whether an ALE instance currently exists in this process,
+/// i.e. whether Ale::new would panic right now.
+pub fn current_instance_exists() -> bool {
+    use std::sync::atomic::Ordering;
+
+    unsafe { INSTANCE_EXISTS.load(Ordering::SeqCst) }
+}
+
+/// Clears the flag marking an ALE instance as alive, without touching the instance.
+///
+/// DANGER: this exists only so test harnesses can recover after a test leaked an
+/// ALE (for instance by forgetting it or panicking across a thread boundary) and
+/// must only be used from tests that are run serially. If an `Ale` is in fact still
+/// alive, calling this lets a second one be created next to it, which is exactly the
+/// unsupported situation the flag guards against, and the two will trample each
+/// other's global state. Never call this outside of a test harness.
+#[cfg(feature = "test-reset")]
+pub unsafe fn force_release_instance() {
+    use std::sync::atomic::Ordering;
+
+    INSTANCE_EXISTS.store(false, Ordering::SeqCst);
+}
+
 unsafe impl Send for Ale {}
 unsafe impl Sync for Ale {}
 
@@ -114,6 +204,12 @@ impl Ale {
         }
     }
 
+    /// A bare Ale has no ROM loaded and therefore no screen, so this always
+    /// returns None. Load a ROM and use Game::screen_dimensions instead.
+    pub fn screen_dimensions(&self) -> Option<(u32, u32)> {
+        None
+    }
+
     /// load_rom loads a rom from the given file name.
     /// This consumes the ALE interface and yields a game (because only one
     /// may be active at a time). The base ALE can be retrieved from the game.
@@ -132,6 +228,12 @@ impl Ale {
 impl Drop for Ale {
     fn drop(&mut self) {
         use std::sync::atomic::Ordering;
+        // A detached ALE (e.g. one that hung) may still be in use
+        // somewhere, so it's leaked rather than freed
+        if self.p.is_null() {
+            return;
+        }
+
         unsafe {
             // If we didn't set the flag we must've set this
             // unsafely so it's questionably safe to free 
@@ -141,4 +243,19 @@ impl Drop for Ale {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enabled_features_match_the_build() {
+        let features = enabled_features();
+
+        assert_eq!(features.contains(&"use_clippy"), cfg!(feature = "use_clippy"));
+        assert_eq!(features.contains(&"test-reset"), cfg!(feature = "test-reset"));
+        assert_eq!(features.contains(&"image"), cfg!(feature = "image"));
+        assert_eq!(features.contains(&"ndarray"), cfg!(feature = "ndarray"));
+    }
+}
@@ -0,0 +1,109 @@
+use ::rand::Rng;
+use ::{Action,AleError};
+
+/// Samples an action according to probs, the probability of each action in
+/// actions. The probabilities are normalized by their sum, so they don't
+/// have to sum to exactly 1.
+pub fn sample_action<R: Rng>(actions: &[Action], probs: &[f32], rng: &mut R) -> Result<Action, AleError> {
+    try!(check_lengths(actions, probs));
+
+    let total: f32 = probs.iter().sum();
+    let mut target = rng.gen::<f32>() * total;
+
+    for (&action, &p) in actions.iter().zip(probs.iter()) {
+        if target < p {
+            return Ok(action);
+        }
+        target -= p;
+    }
+
+    // Rounding can leave target a hair above the last bucket
+    Ok(actions[actions.len() - 1])
+}
+
+/// Picks the action with the highest value. Ties are broken by the lowest
+/// action id, so the choice doesn't depend on the order of actions.
+pub fn argmax_action(actions: &[Action], values: &[f32]) -> Result<Action, AleError> {
+    try!(check_lengths(actions, values));
+
+    let mut best = (actions[0], values[0]);
+    for (&action, &value) in actions.iter().zip(values.iter()).skip(1) {
+        if value > best.1 || (value == best.1 && action.0 < (best.0).0) {
+            best = (action, value);
+        }
+    }
+
+    Ok(best.0)
+}
+
+fn check_lengths(actions: &[Action], weights: &[f32]) -> Result<(), AleError> {
+    if actions.len() != weights.len() {
+        return Err(AleError::LengthMismatch(actions.len(), weights.len()));
+    }
+    if actions.is_empty() {
+        return Err(AleError::NoActions);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::{SeedableRng,StdRng};
+
+    fn actions(ids: &[i32]) -> Vec<Action> {
+        ids.iter().map(|&id| Action(id)).collect()
+    }
+
+    #[test]
+    fn sample_action_follows_the_probabilities() {
+        let actions = actions(&[0, 1, 3]);
+        let mut rng = StdRng::from_seed(&[7][..]);
+        let mut counts = [0; 3];
+
+        for _ in 0..4000 {
+            let action = sample_action(&actions, &[1.0, 3.0, 0.0], &mut rng).unwrap();
+            counts[actions.iter().position(|&a| a == action).unwrap()] += 1;
+        }
+
+        assert_eq!(counts[2], 0);
+        assert!(counts[0] > 800 && counts[0] < 1200, "{:?}", counts);
+        assert!(counts[1] > 2800 && counts[1] < 3200, "{:?}", counts);
+    }
+
+    #[test]
+    fn sample_action_repeats_with_the_same_seed() {
+        let actions = actions(&[0, 1, 2, 3]);
+        let probs = [0.25; 4];
+        let mut a = StdRng::from_seed(&[42][..]);
+        let mut b = StdRng::from_seed(&[42][..]);
+
+        for _ in 0..50 {
+            assert_eq!(sample_action(&actions, &probs, &mut a).unwrap(),
+                       sample_action(&actions, &probs, &mut b).unwrap());
+        }
+    }
+
+    #[test]
+    fn argmax_action_breaks_ties_by_lowest_id() {
+        assert_eq!(argmax_action(&actions(&[3, 1, 2]), &[5.0, 5.0, 1.0]).unwrap(), Action(1));
+        assert_eq!(argmax_action(&actions(&[1, 3, 2]), &[5.0, 5.0, 1.0]).unwrap(), Action(1));
+        assert_eq!(argmax_action(&actions(&[4, 0]), &[1.0, 2.0]).unwrap(), Action(0));
+        assert_eq!(argmax_action(&actions(&[4, 0]), &[2.0, 1.0]).unwrap(), Action(4));
+    }
+
+    #[test]
+    fn mismatched_or_empty_inputs_are_errors() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+
+        match argmax_action(&actions(&[0, 1]), &[1.0]) {
+            Err(AleError::LengthMismatch(2, 1)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match sample_action(&[], &[], &mut rng) {
+            Err(AleError::NoActions) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
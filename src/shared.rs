@@ -0,0 +1,165 @@
+use std::fs::{File,OpenOptions};
+use std::io;
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{fence,Ordering};
+use ::libc::{c_void,size_t,mmap,munmap,PROT_READ,PROT_WRITE,MAP_SHARED,MAP_FAILED};
+use ::{AleError,Frame};
+
+// Header layout, all native endian u32/i32 words:
+// sequence, reward, game_over, width, height, channels
+const HEADER_LEN: usize = 24;
+
+/// An observation read back out of a SharedObsBuffer.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SharedObs {
+    /// Incremented by two on every write, lets a reader tell whether
+    /// a new observation has arrived since it last looked.
+    pub sequence: u32,
+    pub reward: i32,
+    pub game_over: bool,
+    pub frame: Frame,
+}
+
+/// A SharedObsBuffer is a file mapped into memory with MAP_SHARED, so one
+/// process (usually the one owning the Game) can write observations into
+/// it while another reads them without going through a pipe.
+///
+/// Writes are guarded by a sequence number that is odd while a write is in
+/// progress, so readers never see a torn observation. There's no notification
+/// mechanism, readers poll the sequence number.
+pub struct SharedObsBuffer {
+    ptr: *mut u8,
+    len: usize,
+    _file: File,
+}
+
+unsafe impl Send for SharedObsBuffer {}
+
+impl SharedObsBuffer {
+    /// Creates (or truncates) the file at path and maps it, with room for
+    /// observations of up to capacity bytes of pixel data.
+    pub fn create<P: AsRef<Path>>(path: P, capacity: usize) -> io::Result<Self> {
+        let file = try!(OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path));
+        try!(file.set_len((HEADER_LEN + capacity) as u64));
+
+        SharedObsBuffer::map(file, HEADER_LEN + capacity)
+    }
+
+    /// Maps a buffer previously created (by this or another process) at path.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = try!(OpenOptions::new().read(true).write(true).open(path));
+        let len = try!(file.metadata()).len() as usize;
+
+        if len < HEADER_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "file is too small to be a SharedObsBuffer"));
+        }
+
+        SharedObsBuffer::map(file, len)
+    }
+
+    fn map(file: File, len: usize) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let ptr = unsafe {
+            mmap(ptr::null_mut(), len as size_t, PROT_READ | PROT_WRITE, MAP_SHARED, file.as_raw_fd(), 0)
+        };
+
+        if ptr == MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(SharedObsBuffer {
+            ptr: ptr as *mut u8,
+            len: len,
+            _file: file,
+        })
+    }
+
+    /// The number of bytes of pixel data this buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.len - HEADER_LEN
+    }
+
+    /// Writes an observation into the buffer.
+    pub fn write(&mut self, reward: i32, game_over: bool, frame: &Frame) -> Result<(), AleError> {
+        if frame.data.len() > self.capacity() {
+            return Err(AleError::BufferTooSmall(frame.data.len(), self.capacity()));
+        }
+
+        unsafe {
+            let seq = self.word(0);
+            self.set_word(0, seq.wrapping_add(1));
+            fence(Ordering::Release);
+
+            self.set_word(1, reward as u32);
+            self.set_word(2, game_over as u32);
+            self.set_word(3, frame.width as u32);
+            self.set_word(4, frame.height as u32);
+            self.set_word(5, frame.channels as u32);
+            ptr::copy_nonoverlapping(frame.data.as_ptr(), self.ptr.offset(HEADER_LEN as isize), frame.data.len());
+
+            fence(Ordering::Release);
+            self.set_word(0, seq.wrapping_add(2));
+        }
+
+        Ok(())
+    }
+
+    /// The sequence number of the last completed write.
+    pub fn sequence(&self) -> u32 {
+        unsafe { self.word(0) & !1 }
+    }
+
+    /// Reads the most recent observation, spinning while a write is in progress.
+    pub fn read(&self) -> SharedObs {
+        use std::cmp::min;
+
+        loop {
+            unsafe {
+                let before = self.word(0);
+                if before & 1 != 0 {
+                    continue;
+                }
+                fence(Ordering::Acquire);
+
+                let reward = self.word(1) as i32;
+                let game_over = self.word(2) != 0;
+                let width = self.word(3) as usize;
+                let height = self.word(4) as usize;
+                let channels = self.word(5) as usize;
+                let len = min(width * height * channels, self.capacity());
+
+                let mut data = Vec::<u8>::with_capacity(len);
+                ptr::copy_nonoverlapping(self.ptr.offset(HEADER_LEN as isize), data.as_mut_ptr(), len);
+                data.set_len(len);
+
+                fence(Ordering::Acquire);
+                if self.word(0) == before && len == width * height * channels {
+                    return SharedObs {
+                        sequence: before,
+                        reward: reward,
+                        game_over: game_over,
+                        frame: Frame::new(width, height, channels, data),
+                    };
+                }
+            }
+        }
+    }
+
+    unsafe fn word(&self, i: usize) -> u32 {
+        ptr::read_volatile((self.ptr as *const u32).offset(i as isize))
+    }
+
+    unsafe fn set_word(&mut self, i: usize, val: u32) {
+        ptr::write_volatile((self.ptr as *mut u32).offset(i as isize), val)
+    }
+}
+
+impl Drop for SharedObsBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.ptr as *mut c_void, self.len as size_t);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use ::rustc_serialize::json::Json;
+
+/// Describes a box-shaped observation space the way Gym does: every
+/// element of an array of the given shape lies in low..=high.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Space {
+    pub shape: Vec<usize>,
+    pub low: u8,
+    pub high: u8,
+    /// The element type, named like numpy does (e.g. "uint8").
+    pub dtype: &'static str,
+}
+
+impl Space {
+    /// Serializes the space like Gym's Box spaces,
+    /// {"shape": [...], "low": 0, "high": 255, "dtype": "uint8"}.
+    pub fn to_json(&self) -> String {
+        let mut obj = BTreeMap::new();
+        obj.insert("shape".to_owned(), Json::Array(self.shape.iter().map(|&d| Json::U64(d as u64)).collect()));
+        obj.insert("low".to_owned(), Json::U64(self.low as u64));
+        obj.insert("high".to_owned(), Json::U64(self.high as u64));
+        obj.insert("dtype".to_owned(), Json::String(self.dtype.to_owned()));
+
+        Json::Object(obj).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_looks_like_a_gym_box() {
+        let space = Space {
+            shape: vec![210, 160, 3],
+            low: 0,
+            high: 255,
+            dtype: "uint8",
+        };
+
+        assert_eq!(space.to_json(), r#"{"dtype":"uint8","high":255,"low":0,"shape":[210,160,3]}"#);
+
+        let parsed = Json::from_str(&space.to_json()).unwrap();
+        assert_eq!(parsed.find("shape"), Some(&Json::Array(vec![Json::U64(210), Json::U64(160), Json::U64(3)])));
+    }
+}
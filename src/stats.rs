@@ -0,0 +1,109 @@
+/// Tracks the running mean and variance of observations with Welford's
+/// algorithm, for normalizing network inputs by dataset statistics.
+///
+/// Statistics are kept per element of a sample of a fixed size. Every chunk of
+/// that size fed to update counts as one sample, so the same accumulator does
+/// per-pixel statistics (size = width*height*channels, feed whole frames) and
+/// per-channel statistics (size = channels, feed whole frames and every pixel
+/// becomes a sample).
+#[derive(Clone, PartialEq, Debug)]
+pub struct RunningMeanStd {
+    count: u64,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl RunningMeanStd {
+    pub fn new(size: usize) -> RunningMeanStd {
+        RunningMeanStd {
+            count: 0,
+            mean: vec![0.0; size],
+            m2: vec![0.0; size],
+        }
+    }
+
+    /// Adds every sample in data. Panics if data's length isn't a multiple
+    /// of the sample size.
+    pub fn update(&mut self, data: &[u8]) {
+        let size = self.mean.len();
+        assert!(size > 0 && data.len() % size == 0,
+                "data length {} is not a multiple of the sample size {}", data.len(), size);
+
+        for sample in data.chunks(size) {
+            self.count += 1;
+            let n = self.count as f64;
+
+            for ((mean, m2), &x) in self.mean.iter_mut().zip(self.m2.iter_mut()).zip(sample) {
+                let x = x as f64;
+                let delta = x - *mean;
+                *mean += delta / n;
+                *m2 += delta * (x - *mean);
+            }
+        }
+    }
+
+    /// The number of samples seen.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean of every element.
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// The (population) variance of every element, all zero until
+    /// a sample has been seen.
+    pub fn var(&self) -> Vec<f64> {
+        if self.count == 0 {
+            return vec![0.0; self.m2.len()];
+        }
+
+        self.m2.iter().map(|m2| m2 / self.count as f64).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn mean_and_var_match_hand_computed() {
+        let mut stats = RunningMeanStd::new(2);
+        assert_close(&stats.var(), &[0.0, 0.0]);
+
+        stats.update(&[1, 10, 3, 20, 5, 30, 7, 40]);
+
+        assert_eq!(stats.count(), 4);
+        assert_close(stats.mean(), &[4.0, 25.0]);
+        assert_close(&stats.var(), &[5.0, 125.0]);
+    }
+
+    #[test]
+    fn updates_can_be_split_up() {
+        let mut whole = RunningMeanStd::new(2);
+        whole.update(&[1, 10, 3, 20, 5, 30, 7, 40]);
+
+        let mut split = RunningMeanStd::new(2);
+        split.update(&[1, 10]);
+        split.update(&[3, 20, 5, 30]);
+        split.update(&[7, 40]);
+
+        assert_eq!(split.count(), whole.count());
+        assert_close(split.mean(), whole.mean());
+        assert_close(&split.var(), &whole.var());
+    }
+
+    #[test]
+    #[should_panic]
+    fn update_rejects_partial_samples() {
+        RunningMeanStd::new(3).update(&[1, 2]);
+    }
+}
@@ -0,0 +1,259 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use ale::AleError;
+use common::{FIRE,NOOP,RIGHT};
+
+#[test]
+fn act_until_change_runs_to_budget_on_a_still_screen() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert_eq!(game.act_until_change(NOOP, 20), (0, 20));
+}
+
+#[test]
+fn act_until_change_stops_when_the_screen_changes() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let (_, frames) = game.act_until_change(RIGHT, 20);
+    assert!(frames < 20, "moving the paddle didn't change the screen");
+}
+
+#[test]
+fn execute_macro_stops_when_the_game_ends() {
+    let (_serial, mut game) = game_or_skip!(|ale: &mut ale::Ale| ale.set_int("max_num_frames_per_episode", 10));
+
+    let start = game.frame_number();
+    let (reward, over) = game.execute_macro(&[NOOP; 25]);
+
+    assert!(over);
+    assert_eq!(reward, 0);
+    assert!(game.is_over());
+    assert!(game.frame_number() - start <= 10, "actions after the end of the game were applied");
+}
+
+#[test]
+fn reward_history_matches_act_returns() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.track_rewards(true);
+    let rewards: Vec<i32> = (0..300).map(|i| game.act(if i % 2 == 0 { FIRE } else { RIGHT })).collect();
+
+    assert_eq!(game.reward_history(), &rewards[..]);
+
+    game.reset();
+    assert!(game.reward_history().is_empty());
+}
+
+#[test]
+fn frame_skip_and_act_repeat_conflict() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.set_act_repeat(2).unwrap();
+    match game.set_frameskip(4) {
+        Err(AleError::ConflictingFrameSkip(4, 2)) => {}
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+
+    game.set_act_repeat(1).unwrap();
+    game.set_frameskip(4).unwrap();
+    match game.set_act_repeat(2) {
+        Err(AleError::ConflictingFrameSkip(4, 2)) => {}
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+}
+
+#[test]
+fn frame_skip_applies_after_reset() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.set_frameskip(4).unwrap();
+
+    let start = game.frame_number();
+    game.act(NOOP);
+    assert_eq!(game.frame_number() - start, 4);
+
+    game.set_frameskip(0).unwrap();
+    assert_eq!(game.get_int("frame_skip"), 1);
+}
+
+#[test]
+fn frame_skip_needs_a_known_rom() {
+    use std::ffi::CString;
+    use ale::Game;
+    use ale::ffi::{ALE_new,ALE_del,loadROM};
+
+    let rom = match common::rom_path() {
+        Some(rom) => CString::new(rom).unwrap(),
+        None => return,
+    };
+    let _serial = common::Serial::lock();
+
+    unsafe {
+        let p = ALE_new();
+        loadROM(p, rom.as_ptr());
+        let mut game = Game::from_raw_ptr(p);
+
+        match game.set_frameskip(4) {
+            Err(AleError::NoRom) => {}
+            other => panic!("expected NoRom, got {:?}", other),
+        }
+        assert_eq!(game.get_int("frame_skip"), 1);
+
+        drop(game);
+        ALE_del(p);
+    }
+}
+
+#[test]
+fn reward_transform_applies_to_act_but_not_act_raw() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let start = game.clone_system_state();
+    let actions: Vec<_> = (0..200).map(|i| if i % 10 == 0 { FIRE } else { NOOP }).collect();
+
+    let raw: i32 = actions.iter().map(|&a| game.act_raw(a)).sum();
+
+    game.restore_from_cloned_system_state(&start);
+    game.reset_episode_reward();
+    game.set_reward_transform(Box::new(|r| 2 * r + 1));
+    let transformed: i32 = actions.iter().map(|&a| game.act(a)).sum();
+
+    assert_eq!(transformed, 2 * raw + actions.len() as i32);
+    assert_eq!(game.episode_reward(), transformed);
+
+    game.clear_reward_transform();
+    game.restore_from_cloned_system_state(&start);
+    let untransformed: i32 = actions.iter().map(|&a| game.act(a)).sum();
+    assert_eq!(untransformed, raw);
+}
+
+#[test]
+fn act_with_timeout_hands_the_game_back() {
+    use std::time::Duration;
+
+    let (_serial, game) = game_or_skip!();
+
+    let start = game.frame_number();
+    let (game, reward) = game.act_with_timeout(NOOP, Duration::from_secs(10)).unwrap();
+    assert_eq!(reward, 0);
+    assert_eq!(game.frame_number(), start + 1);
+    assert_eq!(game.episode_reward(), 0);
+}
+
+#[test]
+fn streamer_returns_results_in_order() {
+    fn short_episodes(ale: &mut ale::Ale) {
+        ale.set_int("max_num_frames_per_episode", 30);
+    }
+
+    let actions: Vec<_> = (0..40).map(|i| if i % 3 == 0 { RIGHT } else { NOOP }).collect();
+
+    let expected: Vec<_> = {
+        let (_serial, mut game) = game_or_skip!(short_episodes);
+        actions.iter().map(|&a| game.step(a)).collect()
+    };
+    assert!(expected.iter().any(|r| r.game_over) && !expected[0].game_over);
+
+    let (_serial, game) = game_or_skip!(short_episodes);
+    let (tx, rx) = game.into_streamer();
+    for &action in &actions {
+        tx.send(action).unwrap();
+    }
+    drop(tx);
+
+    // The receiver hangs up once the worker has dropped the game
+    let streamed: Vec<_> = rx.iter().collect();
+    assert_eq!(streamed, expected);
+}
+
+#[test]
+fn reset_episode_reward_only_clears_the_total() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.set_reward_transform(Box::new(|_| 2));
+    for _ in 0..5 {
+        game.act(NOOP);
+    }
+    assert_eq!(game.episode_reward(), 10);
+
+    let frame = game.frame_number();
+    let screen = game.screen_rgb();
+    game.reset_episode_reward();
+    assert_eq!(game.episode_reward(), 0);
+    assert_eq!(game.frame_number(), frame);
+    assert_eq!(game.screen_rgb(), screen);
+
+    game.act(NOOP);
+    assert_eq!(game.episode_reward(), 2);
+}
+
+#[test]
+fn evaluate_plays_whole_episodes() {
+    let (_serial, mut game) = game_or_skip!(|ale: &mut ale::Ale| {
+        ale.set_int("max_num_frames_per_episode", 50);
+    });
+
+    // Every act scores 1, so a return counts the acts in its episode
+    game.set_reward_transform(Box::new(|_| 1));
+    let mut calls = 0;
+    let report = game.evaluate(2, |frame| {
+        assert_eq!(frame.width, 160);
+        calls += 1;
+        RIGHT
+    });
+
+    assert_eq!(report.returns.len(), 2);
+    assert_eq!(report.returns[0], report.returns[1]);
+    assert!(report.returns[0] > 0 && report.returns[0] <= 50);
+    assert_eq!(calls, 2 * report.returns[0]);
+    assert_eq!((report.min, report.max), (report.returns[0], report.returns[0]));
+    assert_eq!(report.mean_return, report.returns[0] as f32);
+    assert_eq!(report.std_return, 0.0);
+    assert!(game.is_over());
+
+    let empty = game.evaluate(0, |_| NOOP);
+    assert!(empty.returns.is_empty());
+    assert_eq!(empty.mean_return, 0.0);
+}
+
+#[test]
+fn reset_noops_stay_in_range_and_follow_the_seed() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.reset();
+    let start = game.episode_frame_number();
+    game.set_reset_noops(30);
+
+    let noops = |game: &mut ale::Game, seed| {
+        game.seed_rng(seed);
+        game.reset();
+        let n = game.episode_frame_number() - start;
+        assert!(n >= 0 && n <= 30, "{} noops", n);
+        n
+    };
+
+    let counts: Vec<_> = (0..8).map(|seed| noops(&mut game, seed)).collect();
+    assert!(counts.iter().any(|&n| n != counts[0]), "{:?}", counts);
+    for seed in 0..8 {
+        assert_eq!(noops(&mut game, seed), counts[seed]);
+    }
+}
+
+#[test]
+fn new_best_marks_steps_that_beat_the_episode_high() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let mut script = vec![1, 0, -2, 2, 1, 0, 1].into_iter();
+    game.set_reward_transform(Box::new(move |_| script.next().unwrap_or(0)));
+
+    // Episode totals run 1, 1, -1, 1, 2, 2
+    let bests: Vec<_> = (0..6).map(|_| game.step(NOOP).new_best).collect();
+    assert_eq!(bests, vec![true, false, false, false, true, false]);
+
+    // A reset starts the high score over, so 1 is a new best again
+    game.reset();
+    assert!(game.step(NOOP).new_best);
+}
@@ -0,0 +1,93 @@
+extern crate ale;
+extern crate rustc_serialize;
+
+#[macro_use]
+mod common;
+
+use ale::ActionSpaceKind;
+use common::FIRE;
+
+#[test]
+fn action_space_info_counts_minimal_and_legal_actions() {
+    let (_serial, game) = game_or_skip!();
+
+    let info = game.action_space_info();
+    assert_eq!(info.legal_count, 18);
+    assert!(info.minimal_count > 0);
+    assert!(info.minimal_count <= info.legal_count);
+    assert_eq!(info.minimal.len(), info.minimal_count);
+    assert_eq!(info.minimal, game.minimal_action_set());
+}
+
+#[test]
+fn action_sets_json_lists_ids_and_names() {
+    use rustc_serialize::json::Json;
+
+    let (_serial, game) = game_or_skip!();
+
+    let json = Json::from_str(&game.action_sets_json()).unwrap();
+    let legal = json.find("legal").and_then(|l| l.as_array()).unwrap();
+    let minimal = json.find("minimal").and_then(|m| m.as_array()).unwrap();
+
+    assert_eq!(legal.len(), 18);
+    assert_eq!(minimal.len(), game.minimal_action_set().len());
+    assert_eq!(legal[0].find("id").and_then(|id| id.as_i64()), Some(0));
+    assert_eq!(legal[0].find("name").and_then(|n| n.as_string()), Some("NOOP"));
+    for (entry, action) in minimal.iter().zip(game.minimal_action_set()) {
+        assert_eq!(entry.find("id").and_then(|id| id.as_i64()), Some(action.0 as i64));
+        assert_eq!(entry.find("name").and_then(|n| n.as_string()), Some(action.name()));
+    }
+}
+
+#[test]
+fn has_minimal_action_matches_the_minimal_set() {
+    let (_serial, game) = game_or_skip!();
+
+    let minimal = game.minimal_action_set();
+    for action in game.legal_action_set() {
+        assert_eq!(game.has_minimal_action(action), minimal.contains(&action), "{:?}", action);
+    }
+}
+
+#[test]
+fn raw_games_look_up_minimal_actions_on_demand() {
+    use std::ffi::CString;
+    use ale::Game;
+    use ale::ffi::{ALE_new,ALE_del,loadROM};
+
+    let rom = match common::rom_path() {
+        Some(rom) => CString::new(rom).unwrap(),
+        None => return,
+    };
+    let _serial = common::Serial::lock();
+
+    unsafe {
+        let p = ALE_new();
+        // Wrapping the pointer must not call into an ALE without a ROM
+        let game = Game::from_raw_ptr(p);
+        loadROM(p, rom.as_ptr());
+
+        assert!(game.has_minimal_action(FIRE));
+        assert!(!game.has_minimal_action(ale::Action(2)));
+
+        // The game didn't create the ALE, so it leaves freeing it to us
+        drop(game);
+        ALE_del(p);
+    }
+}
+
+#[test]
+fn action_space_follows_the_chosen_kind() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert_eq!(game.action_space(), game.minimal_action_set());
+
+    game.set_action_space(ActionSpaceKind::Full18);
+    let full = game.action_space();
+    assert_eq!(full.len(), 18);
+    assert_eq!(full[17], ale::Action(17));
+    assert_eq!(game.action_meanings()[1], "FIRE");
+
+    game.set_action_space(ActionSpaceKind::Legal);
+    assert_eq!(game.action_space(), game.legal_action_set());
+}
@@ -0,0 +1,44 @@
+//! Checks that has_minimal_action doesn't allocate. The counting allocator
+//! is global to this test binary, so this file keeps to the one test.
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use std::alloc::{GlobalAlloc,Layout,System};
+use std::sync::atomic::{AtomicUsize,Ordering,ATOMIC_USIZE_INIT};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[test]
+fn has_minimal_action_does_not_allocate() {
+    let (_serial, game) = game_or_skip!();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let mut found = 0;
+    for i in 0..1000 {
+        if game.has_minimal_action(ale::Action(i % 18)) {
+            found += 1;
+        }
+    }
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert!(found > 0);
+    assert_eq!(after - before, 0);
+}
@@ -0,0 +1,63 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use std::fs::{self,File};
+use common::NOOP;
+
+#[test]
+fn checkpoints_rotate_at_the_requested_cadence() {
+    let (_serial, mut game) = game_or_skip!();
+    let dir = common::scratch_dir("checkpoint-cadence").join("nested");
+
+    game.checkpoint_every(10, &dir).unwrap();
+    assert!(dir.is_dir());
+
+    for _ in 0..25 {
+        game.act(NOOP);
+    }
+    let mut names: Vec<_> = fs::read_dir(&dir).unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["checkpoint-0.state", "checkpoint-1.state"]);
+
+    for _ in 0..20 {
+        game.act(NOOP);
+    }
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+    assert!(game.checkpoint_error().is_none());
+
+    let frame = game.frame_number();
+    game.load_checkpoint(dir.join("checkpoint-0.state")).unwrap();
+    assert!(game.frame_number() < frame);
+}
+
+#[test]
+fn checkpoint_every_rejects_a_file_as_the_directory() {
+    let (_serial, mut game) = game_or_skip!();
+    let path = common::scratch_dir("checkpoint-file").join("not-a-dir");
+    File::create(&path).unwrap();
+
+    assert!(game.checkpoint_every(10, &path).is_err());
+    for _ in 0..20 {
+        game.act(NOOP);
+    }
+    assert!(game.checkpoint_error().is_none());
+}
+
+#[test]
+fn a_failed_checkpoint_turns_checkpointing_off() {
+    let (_serial, mut game) = game_or_skip!();
+    let dir = common::scratch_dir("checkpoint-failure").join("gone");
+
+    game.checkpoint_every(5, &dir).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    for _ in 0..20 {
+        game.act(NOOP);
+    }
+    assert!(game.checkpoint_error().is_some());
+    assert!(!dir.exists());
+}
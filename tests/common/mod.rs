@@ -0,0 +1,94 @@
+//! Setup shared by the integration tests.
+//!
+//! Tests that need a game load the ROM named by the ALE_TEST_ROM environment
+//! variable and pass without doing anything when it isn't set. They're written
+//! against Breakout, whose minimal actions are NOOP, FIRE, RIGHT and LEFT and
+//! whose screen stands still until the ball is launched with FIRE.
+//!
+//! Sticky actions are turned off (repeat_action_probability 0) unless a test's
+//! configuration turns them back on, so actions take effect when they're sent.
+//!
+//! Only one ALE may exist per process, so every test that creates one holds
+//! a Serial guard, which makes those tests take turns.
+#![allow(dead_code, unused_macros)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool,Ordering,ATOMIC_BOOL_INIT};
+use std::thread;
+use ale::{Action,Ale,Game};
+
+pub const NOOP: Action = Action(0);
+pub const FIRE: Action = Action(1);
+pub const RIGHT: Action = Action(3);
+pub const LEFT: Action = Action(4);
+
+/// Evaluates to a (Serial, Game) for the test ROM, optionally configuring the
+/// ALE with a closure before the ROM is loaded, or returns from the test if
+/// there's no test ROM.
+macro_rules! game_or_skip {
+    () => {
+        game_or_skip!(|_| {})
+    };
+    ($configure:expr) => {
+        match ::common::configured_game($configure) {
+            Some(game) => game,
+            None => return,
+        }
+    };
+}
+
+static BUSY: AtomicBool = ATOMIC_BOOL_INIT;
+
+/// Held while a test uses the ALE, released on drop (including when the test panics).
+pub struct Serial;
+
+impl Serial {
+    pub fn lock() -> Serial {
+        while BUSY.compare_and_swap(false, true, Ordering::SeqCst) {
+            thread::yield_now();
+        }
+
+        Serial
+    }
+}
+
+impl Drop for Serial {
+    fn drop(&mut self) {
+        BUSY.store(false, Ordering::SeqCst);
+    }
+}
+
+pub fn rom_path() -> Option<String> {
+    env::var("ALE_TEST_ROM").ok()
+}
+
+/// Creates an ALE without sticky actions, lets configure change its settings,
+/// and loads the test ROM.
+/// Bind the result as (_serial, game) so the game is dropped before the guard.
+pub fn configured_game<F: FnOnce(&mut Ale)>(configure: F) -> Option<(Serial, Game)> {
+    let path = match rom_path() {
+        Some(path) => path,
+        None => {
+            println!("ALE_TEST_ROM isn't set, skipping");
+            return None;
+        }
+    };
+
+    let serial = Serial::lock();
+    let mut ale = Ale::new();
+    ale.set_float("repeat_action_probability", 0.0);
+    configure(&mut ale);
+
+    Some((serial, ale.load_rom(&path)))
+}
+
+/// Returns an empty directory for a test to write files into.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("ale-test-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Could not create scratch directory");
+
+    dir
+}
@@ -0,0 +1,101 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use ale::{AleError,DemoRecorder};
+use common::{RIGHT,LEFT};
+
+#[test]
+fn replays_hold_actions_until_the_next_recorded_frame() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.reset();
+    let mut demo = DemoRecorder::new();
+    let mut reward = 0;
+    let mut held = None;
+    for t in 0..40 {
+        let action = if (t / 8) % 2 == 0 { RIGHT } else { LEFT };
+        // Only changes of action are recorded
+        if held != Some(action) {
+            demo.record(game.episode_frame_number(), action);
+            held = Some(action);
+        }
+        reward += game.act(action);
+    }
+    assert_eq!(demo.steps.len(), 5);
+    let screen = game.screen_rgb();
+    let frame = game.episode_frame_number();
+
+    let (replayed, _) = game.replay_recording(&demo).unwrap();
+    assert_eq!(replayed, reward);
+    // The replay stops after the last recorded action rather than holding it
+    assert_eq!(game.episode_frame_number(), frame - 7);
+
+    for _ in 0..7 {
+        game.act(RIGHT);
+    }
+    assert!(game.screen_rgb() == screen);
+}
+
+#[test]
+fn saved_demos_replay_from_disk() {
+    let (_serial, mut game) = game_or_skip!();
+
+    // Every action is recorded, so the replay ends where the recording did
+    game.reset();
+    let mut demo = DemoRecorder::new();
+    let mut reward = 0;
+    for t in 0..30 {
+        let action = if t < 15 { RIGHT } else { LEFT };
+        demo.record(game.episode_frame_number(), action);
+        reward += game.act(action);
+    }
+    let screen = game.screen_rgb();
+
+    let path = common::scratch_dir("demo").join("demo.json");
+    demo.save(&path).unwrap();
+
+    let (replayed, _) = game.replay_demo(&path).unwrap();
+    assert_eq!(replayed, reward);
+    assert!(game.screen_rgb() == screen);
+}
+
+#[test]
+fn replays_that_overshoot_a_recorded_frame_are_errors() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.reset();
+    let start = game.episode_frame_number();
+    let mut demo = DemoRecorder::new();
+    demo.record(start, RIGHT);
+    demo.record(start + 5, LEFT);
+
+    game.replay_recording(&demo).unwrap();
+
+    game.set_act_repeat(4).unwrap();
+    match game.replay_recording(&demo) {
+        Err(AleError::DemoDrift(recorded, actual)) => assert_eq!((recorded, actual), (start + 5, start + 8)),
+        other => panic!("expected the replay to drift, got {:?}", other),
+    }
+}
+
+#[test]
+fn replays_ignore_reset_noops() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.reset();
+    let start = game.episode_frame_number();
+    let mut demo = DemoRecorder::new();
+    demo.record(start, RIGHT);
+    demo.record(start + 10, LEFT);
+    game.replay_recording(&demo).unwrap();
+    let screen = game.screen_rgb();
+
+    game.set_reset_noops(30);
+    for seed in 0..4 {
+        game.seed_rng(seed);
+        game.replay_recording(&demo).unwrap();
+        assert!(game.screen_rgb() == screen);
+    }
+}
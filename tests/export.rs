@@ -0,0 +1,53 @@
+#![cfg(feature = "image")]
+extern crate ale;
+extern crate rustc_serialize;
+
+#[macro_use]
+mod common;
+
+use std::fs::File;
+use std::io::Read;
+use common::RIGHT;
+
+fn short_episodes(ale: &mut ale::Ale) {
+    ale.set_int("max_num_frames_per_episode", 20);
+}
+
+#[test]
+fn run_and_save_gif_writes_a_whole_gif() {
+    let (_serial, mut game) = game_or_skip!(short_episodes);
+    let path = common::scratch_dir("gif").join("episode.gif");
+
+    let mut steps = 0;
+    game.run_and_save_gif(|frame| {
+        assert_eq!((frame.width, frame.height), (160, 210));
+        steps += 1;
+        RIGHT
+    }, &path, 30).unwrap();
+    assert!(steps > 0 && steps <= 20);
+    assert!(game.is_over());
+
+    let mut bytes = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+    assert_eq!(&bytes[..6], b"GIF89a");
+    // The logical screen size, little endian
+    assert_eq!(&bytes[6..10], &[160, 0, 210, 0]);
+    assert_eq!(bytes.last(), Some(&0x3b));
+}
+
+#[test]
+fn screen_data_uri_holds_a_png() {
+    use rustc_serialize::base64::FromBase64;
+
+    let (_serial, game) = game_or_skip!();
+
+    let uri = game.screen_data_uri();
+    let prefix = "data:image/png;base64,";
+    assert!(uri.starts_with(prefix));
+
+    let png = uri[prefix.len()..].from_base64().unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // IHDR holds the width and height, big endian
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 160, 0, 0, 0, 210]);
+}
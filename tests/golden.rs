@@ -0,0 +1,68 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use common::{FIRE,LEFT,NOOP,RIGHT};
+
+fn actions() -> Vec<ale::Action> {
+    (0..60).map(|i| match i % 4 {
+        0 => FIRE,
+        1 => RIGHT,
+        2 => LEFT,
+        _ => NOOP,
+    }).collect()
+}
+
+#[test]
+fn golden_traces_check_out_against_themselves() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let trace = game.record_golden(&actions());
+    assert_eq!(trace.actions, actions());
+    assert_eq!(trace.steps.len(), 60);
+    assert_eq!(game.check_golden(&trace), Ok(()));
+}
+
+#[test]
+fn altered_traces_diverge_where_they_were_changed() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let trace = game.record_golden(&actions());
+
+    let mut wrong_reward = trace.clone();
+    wrong_reward.steps[10].0 += 1;
+    assert_eq!(game.check_golden(&wrong_reward), Err(10));
+
+    let mut wrong_screen = trace.clone();
+    wrong_screen.steps[20].1 ^= 1;
+    assert_eq!(game.check_golden(&wrong_screen), Err(20));
+
+    // Moving the paddle the other way changes the very next screen
+    let mut wrong_action = trace.clone();
+    wrong_action.actions[5] = LEFT;
+    assert_eq!(game.check_golden(&wrong_action), Err(5));
+
+    let mut truncated = trace.clone();
+    truncated.steps.truncate(30);
+    assert_eq!(game.check_golden(&truncated), Err(30));
+}
+
+#[test]
+fn golden_traces_ignore_reset_noops() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.reset();
+    let start = game.episode_frame_number();
+
+    game.set_reset_noops(30);
+    game.seed_rng(1);
+    let trace = game.record_golden(&actions());
+    assert_eq!(game.episode_frame_number(), start + 60);
+
+    for seed in 2..6 {
+        game.seed_rng(seed);
+        assert_eq!(game.check_golden(&trace), Ok(()));
+        assert_eq!(game.episode_frame_number(), start + 60);
+    }
+}
@@ -0,0 +1,53 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use std::thread;
+use ale::Ale;
+use common::Serial;
+
+#[test]
+fn a_second_instance_panics() {
+    let _serial = Serial::lock();
+    let _first = Ale::new();
+
+    assert!(thread::spawn(|| { Ale::new(); }).join().is_err());
+}
+
+#[cfg(feature = "test-reset")]
+#[test]
+fn force_release_recovers_from_a_leaked_instance() {
+    use std::mem;
+
+    let _serial = Serial::lock();
+
+    mem::forget(Ale::new());
+    assert!(ale::current_instance_exists());
+
+    unsafe {
+        ale::force_release_instance();
+    }
+    assert!(!ale::current_instance_exists());
+
+    drop(Ale::new());
+    assert!(!ale::current_instance_exists());
+}
+
+#[test]
+fn current_instance_exists_tracks_the_instance() {
+    let _serial = Serial::lock();
+
+    assert!(!ale::current_instance_exists());
+    let ale = Ale::new();
+    assert!(ale::current_instance_exists());
+    drop(ale);
+    assert!(!ale::current_instance_exists());
+}
+
+#[test]
+fn a_fresh_ale_has_no_screen_dimensions() {
+    let _serial = Serial::lock();
+
+    assert_eq!(Ale::new().screen_dimensions(), None);
+}
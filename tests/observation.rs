@@ -0,0 +1,238 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use common::{NOOP,RIGHT,LEFT};
+
+#[test]
+fn diff_stack_shows_motion() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.diff_stack(2);
+    game.act(RIGHT);
+    game.act(RIGHT);
+    let stack = game.diff_stack(2);
+
+    let (width, height) = game.screen_dimensions();
+    let size = (width * height) as usize;
+    assert_eq!(stack.len(), 2 * size);
+    assert!(stack[..size].iter().any(|&d| d != 0), "older layer shows no motion");
+    assert!(stack[size..].iter().any(|&d| d != 0), "newer layer shows no motion");
+}
+
+#[test]
+fn diff_stack_restarts_after_restore() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let state = game.clone_state();
+    game.diff_stack(1);
+    game.act(RIGHT);
+    game.restore_from_cloned_state(&state);
+
+    assert!(game.diff_stack(1).iter().all(|&d| d == 0));
+
+    // The screen from before the restore isn't redrawn until this act, and
+    // must not be taken as the frame before it
+    game.act(LEFT);
+    assert!(game.diff_stack(1).iter().all(|&d| d == 0));
+
+    game.act(RIGHT);
+    assert!(game.diff_stack(1).iter().any(|&d| d != 0));
+}
+
+#[test]
+fn frame_history_can_be_turned_off() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.diff_stack(1);
+    game.act(RIGHT);
+    game.set_frame_history_len(0);
+    game.act(NOOP);
+
+    // Recording starts over with the current screen
+    assert!(game.diff_stack(1).iter().all(|&d| d == 0));
+}
+
+#[test]
+fn observe_full_has_screen_and_ram() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.act(RIGHT);
+    let obs = game.observe_full();
+
+    let (width, height) = game.screen_dimensions();
+    assert_eq!((obs.frame.width, obs.frame.height, obs.frame.channels), (width as usize, height as usize, 3));
+    assert_eq!(obs.frame.data, game.screen_rgb());
+    assert_eq!(obs.ram.len(), game.ram_size() as usize);
+    assert_eq!(obs.ram, game.ram());
+}
+
+#[test]
+fn obs_cache_hits_return_the_same_observation() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert_eq!(game.obs_cache_stats(), None);
+    game.enable_obs_cache(8);
+
+    let first = game.observation();
+    assert_eq!(game.obs_cache_stats(), Some((0, 1)));
+
+    // A hit is served without fetching the screen again
+    let fetches = game.screen_fetches();
+    let second = game.observation();
+    assert_eq!(game.obs_cache_stats(), Some((1, 1)));
+    assert_eq!(game.screen_fetches(), fetches);
+    assert_eq!(first, second);
+
+    game.act(RIGHT);
+    game.observation();
+    assert_eq!(game.obs_cache_stats(), Some((1, 2)));
+    assert!(game.screen_fetches() > fetches);
+}
+
+#[test]
+fn observations_follow_the_resize_target() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let native = game.observation();
+    assert_eq!((native.width, native.height, native.channels), (160, 210, 3));
+
+    game.set_resize(Some((84, 84)));
+    let resized = game.observation();
+    assert_eq!((resized.width, resized.height, resized.channels), (84, 84, 3));
+    assert_eq!(resized.data.len(), 84 * 84 * 3);
+    assert_eq!(game.observation_space().shape, vec![84, 84, 3]);
+
+    game.set_resize(None);
+    assert_eq!(game.observation().width, 160);
+}
+
+#[test]
+fn ram_range_covers_the_whole_ram() {
+    let (_serial, game) = game_or_skip!();
+
+    let range = game.ram_range();
+    assert_eq!(range.start, 0);
+    assert_eq!(range.end, game.ram_size() as usize);
+    assert_eq!(range.end, game.ram().len());
+    assert_eq!(range.end, 128);
+}
+
+#[test]
+fn motion_bbox_surrounds_the_moving_paddle() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert_eq!(game.motion_bbox(), None);
+    game.act(NOOP);
+    assert_eq!(game.motion_bbox(), None);
+
+    game.act(RIGHT);
+    let bbox = game.motion_bbox().expect("moving the paddle should change the screen");
+    assert!(bbox.width > 0 && bbox.height > 0);
+    assert!(bbox.x + bbox.width <= 160 && bbox.y + bbox.height <= 210);
+    // The paddle lives in the bottom part of the screen
+    assert!(bbox.y > 150);
+}
+
+#[test]
+fn obs_processor_runs_after_the_resize() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let plain = game.observation();
+    game.set_obs_processor(Box::new(|mut frame| {
+        for p in &mut frame.data {
+            *p = 255 - *p;
+        }
+        frame
+    }));
+
+    let inverted = game.observation();
+    assert_eq!(inverted.data.len(), plain.data.len());
+    assert!(inverted.data.iter().zip(&plain.data).all(|(&i, &p)| i == 255 - p));
+
+    game.set_resize(Some((84, 84)));
+    assert_eq!(game.observation().width, 84);
+
+    game.clear_obs_processor();
+    game.set_resize(None);
+    assert_eq!(game.observation(), plain);
+}
+
+#[test]
+fn attach_fifo_replaces_a_regular_file() {
+    use std::fs::{self,File};
+    use std::io::{Read,Write};
+
+    let (_serial, mut game) = game_or_skip!();
+    let path = common::scratch_dir("fifo").join("frames.raw");
+    let frame_bytes = 12 + 160 * 210 * 3;
+
+    // Leftovers longer than what gets streamed must not survive
+    File::create(&path).unwrap().write_all(&vec![0xAA; 3 * frame_bytes]).unwrap();
+
+    game.attach_fifo(&path).unwrap();
+    game.act(RIGHT);
+    game.act(NOOP);
+    let streamed = game.screen_rgb();
+    game.detach_fifo();
+    game.act(NOOP);
+
+    let mut bytes = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 2 * frame_bytes);
+    assert_eq!(&bytes[..8], &[160, 0, 0, 0, 210, 0, 0, 0]);
+    assert_eq!(&bytes[frame_bytes..frame_bytes + 8], &[160, 0, 0, 0, 210, 0, 0, 0]);
+    assert!(&bytes[frame_bytes + 12..] == &streamed[..]);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn observation_space_describes_every_obs_kind() {
+    use ale::{ObsKind,Observation};
+
+    let (_serial, mut game) = game_or_skip!();
+    game.set_resize(Some((84, 84)));
+
+    let observe_len = |game: &mut ale::Game| game.observe().into_bytes().len();
+    let space_len = |game: &ale::Game| game.observation_space().shape.iter().product::<usize>();
+
+    assert_eq!(game.observation_space().shape, vec![84, 84, 3]);
+    assert_eq!(observe_len(&mut game), space_len(&game));
+
+    game.set_obs_kind(ObsKind::Grayscale);
+    assert_eq!(game.observation_space().shape, vec![84, 84, 1]);
+    assert_eq!(observe_len(&mut game), space_len(&game));
+
+    game.set_obs_kind(ObsKind::RamPixel);
+    assert_eq!(game.observation_space().shape, vec![128 + 84 * 84]);
+    match game.observe() {
+        Observation::RamPixel { ram, frame } => {
+            assert_eq!(ram.len(), 128);
+            assert_eq!((frame.width, frame.height, frame.channels), (84, 84, 1));
+        }
+        other => panic!("expected a RamPixel observation, got {:?}", other),
+    }
+    assert_eq!(observe_len(&mut game), space_len(&game));
+}
+
+#[test]
+fn block_motion_picks_up_the_paddle() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let still = game.block_motion(16);
+    assert_eq!(still.len(), (160 / 16) * (210 / 16));
+    assert!(still.iter().all(|&v| v == (0, 0)));
+
+    game.act(RIGHT);
+    let moved = game.block_motion(16);
+    assert_eq!(moved.len(), still.len());
+    // Only the paddle, in the bottom rows of tiles, moves
+    for (i, &v) in moved.iter().enumerate() {
+        if v != (0, 0) {
+            assert!(i / 10 >= 10, "tile {} moved by {:?}", i, v);
+        }
+    }
+    assert!(moved.iter().any(|&(dx, _)| dx > 0));
+}
@@ -0,0 +1,93 @@
+#![cfg(unix)]
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use std::env;
+use std::process::Command;
+use ale::{Frame,SharedObsBuffer};
+use common::RIGHT;
+
+// Set in the child process started by observations_cross_processes
+const CHILD_PATH_VAR: &'static str = "ALE_SHARED_OBS_CHILD";
+
+fn pattern(width: usize, height: usize) -> Frame {
+    Frame::new(width, height, 3, (0..width * height * 3).map(|i| (i % 251) as u8).collect())
+}
+
+#[test]
+fn observations_cross_processes() {
+    let path = common::scratch_dir("shared-obs").join("obs");
+    let mut buf = SharedObsBuffer::create(&path, 16 * 8 * 3).unwrap();
+
+    buf.write(7, false, &pattern(16, 8)).unwrap();
+
+    let status = Command::new(env::current_exe().unwrap())
+        .args(&["shared_obs_child", "--exact", "--test-threads=1"])
+        .env(CHILD_PATH_VAR, &path)
+        .status()
+        .unwrap();
+    assert!(status.success(), "the child process failed");
+
+    // The child answers with the inverted frame and the next reward
+    let obs = buf.read();
+    assert_eq!(obs.sequence, 4);
+    assert_eq!(obs.reward, 8);
+    assert!(obs.game_over);
+    let inverted: Vec<u8> = pattern(16, 8).data.iter().map(|&v| 255 - v).collect();
+    assert_eq!(obs.frame.data, inverted);
+}
+
+#[test]
+fn shared_obs_child() {
+    let path = match env::var(CHILD_PATH_VAR) {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+
+    let mut buf = SharedObsBuffer::open(&path).unwrap();
+    let obs = buf.read();
+    assert_eq!(obs.sequence, 2);
+    assert_eq!(obs.reward, 7);
+    assert!(!obs.game_over);
+    assert_eq!(obs.frame, pattern(16, 8));
+
+    let mut answer = obs.frame.clone();
+    for v in answer.data.iter_mut() {
+        *v = 255 - *v;
+    }
+    buf.write(obs.reward + 1, true, &answer).unwrap();
+}
+
+#[test]
+fn step_into_shared_writes_the_frame() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let (width, height) = game.screen_dimensions();
+    let path = common::scratch_dir("step-into-shared").join("obs");
+    let mut buf = SharedObsBuffer::create(&path, (width * height * 3) as usize).unwrap();
+
+    let reward = game.step_into_shared(RIGHT, &mut buf).unwrap();
+
+    let obs = buf.read();
+    assert_eq!(obs.reward, reward);
+    assert_eq!(obs.game_over, game.is_over());
+    assert_eq!(obs.frame, game.frame());
+}
+
+#[test]
+fn step_into_shared_checks_the_buffer_before_acting() {
+    let (_serial, mut game) = game_or_skip!();
+
+    let path = common::scratch_dir("step-into-small-shared").join("obs");
+    let mut buf = SharedObsBuffer::create(&path, 16).unwrap();
+    let frame = game.frame_number();
+
+    match game.step_into_shared(RIGHT, &mut buf) {
+        Err(ale::AleError::BufferTooSmall(_, 16)) => {}
+        other => panic!("expected BufferTooSmall, got {:?}", other),
+    }
+    assert_eq!(game.frame_number(), frame);
+    assert_eq!(buf.sequence(), 0);
+}
@@ -0,0 +1,63 @@
+extern crate ale;
+extern crate rustc_serialize;
+
+#[macro_use]
+mod common;
+
+use std::fs::File;
+use std::io::Read;
+use ale::{ActionSpaceKind,ObsKind};
+use rustc_serialize::json::Json;
+
+#[test]
+fn exported_specs_validate_until_the_game_changes() {
+    let (_serial, mut game) = game_or_skip!();
+    let path = common::scratch_dir("spec").join("env.json");
+
+    game.export_env_spec(&path).unwrap();
+    assert!(game.validate_env_spec(&path).unwrap());
+
+    game.set_act_repeat(4).unwrap();
+    assert!(!game.validate_env_spec(&path).unwrap());
+    game.set_act_repeat(1).unwrap();
+    assert!(game.validate_env_spec(&path).unwrap());
+
+    game.set_action_space(ActionSpaceKind::Full18);
+    assert!(!game.validate_env_spec(&path).unwrap());
+    game.set_action_space(ActionSpaceKind::Minimal);
+
+    game.set_obs_kind(ObsKind::Grayscale);
+    assert!(!game.validate_env_spec(&path).unwrap());
+}
+
+#[test]
+fn exported_specs_describe_the_game() {
+    let (_serial, mut game) = game_or_skip!();
+    let path = common::scratch_dir("spec-contents").join("env.json");
+
+    game.set_obs_kind(ObsKind::Grayscale);
+    game.export_env_spec(&path).unwrap();
+
+    let mut contents = String::new();
+    File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+    let spec = Json::from_str(&contents).unwrap();
+
+    let actions = spec.find("action_space").and_then(|a| a.as_array()).unwrap();
+    assert_eq!(actions.len(), game.minimal_action_set().len());
+    let shape = spec.find_path(&["observation_space", "shape"]).and_then(|s| s.as_array()).unwrap();
+    assert_eq!(shape, &vec![Json::U64(210), Json::U64(160), Json::U64(1)]);
+    assert_eq!(spec.find_path(&["settings", "repeat_action_probability"]).and_then(|p| p.as_f64()), Some(0.0));
+    assert_eq!(spec.find_path(&["settings", "act_repeat"]).and_then(|r| r.as_u64()), Some(1));
+    assert!(spec.find("rom_checksum").and_then(|c| c.as_string()).is_some());
+}
+
+#[test]
+fn validating_against_garbage_is_an_error() {
+    use std::io::Write;
+
+    let (_serial, game) = game_or_skip!();
+    let path = common::scratch_dir("spec-garbage").join("env.json");
+    File::create(&path).unwrap().write_all(b"not json").unwrap();
+
+    assert!(game.validate_env_spec(&path).is_err());
+}
@@ -0,0 +1,300 @@
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+use ale::Game;
+use common::{NOOP,RIGHT,LEFT};
+
+// Plays a fixed sequence and collects the screens it produces. With sticky
+// actions on, the screens depend on the emulator's RNG, so two runs from the
+// same system state only match if nothing in between disturbed it.
+fn play(game: &mut Game) -> Vec<Vec<u8>> {
+    (0..20).map(|i| {
+        game.act(if i % 2 == 0 { RIGHT } else { NOOP });
+        game.screen_rgb()
+    }).collect()
+}
+
+fn sticky(ale: &mut ale::Ale) {
+    ale.set_float("repeat_action_probability", 0.25);
+}
+
+#[test]
+fn diff_states_shows_motion_and_leaves_the_game_alone() {
+    let (_serial, mut game) = game_or_skip!(sticky);
+
+    let a = game.clone_state();
+    for _ in 0..5 {
+        game.act(RIGHT);
+    }
+    let b = game.clone_state();
+
+    let before = game.clone_system_state();
+    let diff = game.diff_states(&a, &b);
+    assert_eq!((diff.width, diff.height), (160, 210));
+    assert!(diff.data.iter().any(|&x| x != 0));
+    assert!(game.diff_states(&a, &a).data.iter().all(|&x| x == 0));
+
+    let probed = play(&mut game);
+    game.restore_from_cloned_system_state(&before);
+    assert!(probed == play(&mut game));
+}
+
+#[test]
+fn undo_restores_the_emulator_and_the_bookkeeping() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.track_rewards(true);
+    game.set_reward_transform(Box::new(|_| 1));
+    game.diff_stack(2);
+    for _ in 0..3 {
+        game.act(RIGHT);
+    }
+
+    let frame = game.frame();
+    let ram = game.ram();
+    let stack = game.diff_stack(2);
+    let reward = game.episode_reward();
+    let history = game.reward_history().to_vec();
+
+    let (result, token) = game.step_undoable(RIGHT);
+    assert_eq!(result.reward, 1);
+    assert_eq!(game.episode_reward(), reward + 1);
+    let stepped = game.screen_rgb();
+    assert!(stepped != frame.data);
+
+    game.undo(&token);
+    assert_eq!(token.frame(), &frame);
+    assert_eq!(game.ram(), ram);
+    assert_eq!(game.episode_reward(), reward);
+    assert_eq!(game.reward_history(), &history[..]);
+    assert_eq!(game.diff_stack(2), stack);
+
+    game.act(RIGHT);
+    assert_eq!(game.screen_rgb(), stepped);
+}
+
+#[test]
+fn frames_to_death_counts_frames_and_leaves_the_game_alone() {
+    let (_serial, mut game) = game_or_skip!(|ale: &mut ale::Ale| {
+        sticky(ale);
+        ale.set_int("max_num_frames_per_episode", 500);
+    });
+
+    let before = game.clone_system_state();
+    let frame = game.frame_number();
+    let frames = game.frames_to_death(NOOP, 1000).expect("the frame limit ends the episode");
+    assert!(frames > 0 && frames <= 500);
+    assert_eq!(game.frames_to_death(NOOP, 10), None);
+    assert_eq!(game.frame_number(), frame);
+    assert!(!game.is_over());
+
+    let probed = play(&mut game);
+    game.restore_from_cloned_system_state(&before);
+    assert!(probed == play(&mut game));
+}
+
+#[test]
+fn is_stuck_notices_a_frozen_ram() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert!(!game.is_stuck(4));
+    for _ in 0..5 {
+        game.act(RIGHT);
+    }
+    assert!(!game.is_stuck(4));
+
+    // Replaying the same frame over and over leaves the RAM identical after
+    // every act, and it takes five of those for four acts to change nothing
+    let frozen = game.clone_state();
+    game.act(RIGHT);
+    for _ in 0..4 {
+        game.restore_from_cloned_state(&frozen);
+        game.act(NOOP);
+    }
+    assert!(!game.is_stuck(4));
+
+    game.restore_from_cloned_state(&frozen);
+    game.act(NOOP);
+    assert!(game.is_stuck(4));
+
+    game.act(RIGHT);
+    assert!(!game.is_stuck(4));
+}
+
+#[test]
+fn is_stuck_over_one_act_needs_that_act_to_leave_the_ram_alone() {
+    let (_serial, mut game) = game_or_skip!();
+
+    assert!(!game.is_stuck(1));
+    game.act(RIGHT);
+    assert!(!game.is_stuck(1));
+
+    // The paddle moves, so the last act changed the RAM
+    game.act(RIGHT);
+    assert!(!game.is_stuck(1));
+
+    let frozen = game.clone_state();
+    for _ in 0..2 {
+        game.restore_from_cloned_state(&frozen);
+        game.act(NOOP);
+    }
+    assert!(game.is_stuck(1));
+}
+
+#[test]
+fn self_check_passes_and_leaves_the_game_alone() {
+    let (_serial, mut game) = game_or_skip!(sticky);
+
+    for _ in 0..3 {
+        game.act(RIGHT);
+    }
+    let before = game.clone_system_state();
+    let frame = game.frame_number();
+
+    game.self_check().unwrap();
+    assert_eq!(game.frame_number(), frame);
+
+    let probed = play(&mut game);
+    game.restore_from_cloned_system_state(&before);
+    assert!(probed == play(&mut game));
+}
+
+#[test]
+fn compressed_states_round_trip() {
+    let (_serial, mut game) = game_or_skip!();
+
+    for _ in 0..5 {
+        game.act(RIGHT);
+    }
+    let compressed = game.clone_state_compressed();
+    assert!(!compressed.is_empty());
+    assert_eq!(compressed.len(), compressed.clone().len());
+
+    let ram = game.ram();
+    let frame = game.frame_number();
+    game.act(RIGHT);
+    game.act(RIGHT);
+    let after = game.screen_rgb();
+
+    game.restore_from_compressed(&compressed);
+    assert_eq!(game.ram(), ram);
+    assert_eq!(game.frame_number(), frame);
+    game.act(RIGHT);
+    game.act(RIGHT);
+    assert!(game.screen_rgb() == after);
+}
+
+#[test]
+fn soft_resets_return_to_the_start() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.soft_reset();
+    let frame = game.frame_number();
+    let ram = game.ram();
+
+    for _ in 0..10 {
+        game.act(RIGHT);
+    }
+    game.soft_reset();
+    assert_eq!(game.frame_number(), frame);
+    assert_eq!(game.ram(), ram);
+    assert_eq!(game.episode_reward(), 0);
+}
+
+#[test]
+fn soft_resets_leave_the_last_episode_out_of_the_frame_history() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.soft_reset();
+    game.diff_stack(1);
+    for _ in 0..10 {
+        game.act(RIGHT);
+    }
+
+    // The restored start isn't drawn until the next act, so the last screen
+    // of the previous episode must not be the frame before it
+    game.soft_reset();
+    game.act(LEFT);
+    assert!(game.diff_stack(1).iter().all(|&d| d == 0));
+}
+
+#[test]
+fn seeded_soft_resets_vary_the_noops_reproducibly() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.soft_reset();
+    let start = game.frame_number();
+    game.set_reset_noops(30);
+
+    let noops = |game: &mut ale::Game, seed| {
+        game.soft_reset_seeded(seed);
+        let n = game.frame_number() - start;
+        assert!(n >= 0 && n <= 30);
+        n
+    };
+
+    let counts: Vec<_> = (0..8).map(|seed| noops(&mut game, seed)).collect();
+    assert!(counts.iter().any(|&n| n != counts[0]), "{:?}", counts);
+    for seed in 0..8 {
+        assert_eq!(noops(&mut game, seed), counts[seed]);
+    }
+}
+
+#[test]
+fn detect_score_region_finds_the_top_score_and_leaves_the_game_alone() {
+    let (_serial, mut game) = game_or_skip!(sticky);
+
+    let before = game.clone_system_state();
+    let frame = game.frame_number();
+
+    let region = game.detect_score_region(2000).expect("random play should score in Breakout");
+    // Breakout draws the score along the top of the screen
+    assert!(region.y < 20, "{:?}", region);
+    assert_eq!(game.frame_number(), frame);
+
+    let probed = play(&mut game);
+    game.restore_from_cloned_system_state(&before);
+    assert!(probed == play(&mut game));
+}
+
+#[test]
+fn state_bytes_round_trip_through_disk() {
+    use std::fs::File;
+    use std::io::{Read,Write};
+
+    let (_serial, mut game) = game_or_skip!(sticky);
+    let path = common::scratch_dir("state-bytes").join("state.bin");
+
+    for _ in 0..5 {
+        game.act(RIGHT);
+    }
+    File::create(&path).unwrap().write_all(&game.save_state_bytes_and_free()).unwrap();
+    let ram = game.ram();
+    let expected = play(&mut game);
+
+    let mut bytes = Vec::new();
+    File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+    game.restore_state_bytes(&bytes);
+    assert_eq!(game.ram(), ram);
+    // System states include the RNG, so even sticky actions replay the same
+    assert!(play(&mut game) == expected);
+}
+
+#[test]
+fn detect_score_region_leaves_the_reset_noops_alone() {
+    let (_serial, mut game) = game_or_skip!();
+
+    game.set_reset_noops(30);
+    let noops = |game: &mut ale::Game, probe: bool| {
+        game.seed_rng(3);
+        if probe {
+            game.detect_score_region(50);
+        }
+        game.reset();
+        game.episode_frame_number()
+    };
+
+    assert_eq!(noops(&mut game, true), noops(&mut game, false));
+}
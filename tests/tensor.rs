@@ -0,0 +1,22 @@
+#![cfg(feature = "ndarray")]
+extern crate ale;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn obs_tensor_is_chw_in_the_unit_range() {
+    let (_serial, game) = game_or_skip!();
+
+    let tensor = game.obs_tensor();
+    assert_eq!(tensor.shape(), &[3, 210, 160]);
+    assert!(tensor.iter().all(|&v| v >= 0.0 && v <= 1.0));
+
+    let rgb = game.screen_rgb();
+    for &(y, x) in &[(0, 0), (5, 17), (192, 80), (209, 159)] {
+        for c in 0..3 {
+            let expected = rgb[(y * 160 + x) * 3 + c] as f32 / 255.0;
+            assert_eq!(tensor[[c, y, x]], expected);
+        }
+    }
+}